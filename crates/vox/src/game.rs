@@ -2,6 +2,7 @@ use crate::personal_world::PersonalWorld;
 use std::time::Instant;
 use vox_render::renderer::renderer::Renderer;
 use vox_world::world::small_world::SmallWorld;
+use vox_world::world::world_info::WorldInfo;
 use winit::dpi::PhysicalSize;
 use winit::event::Event;
 use winit::window::Window;
@@ -13,13 +14,18 @@ use winit_window_control::main_loop::{
 pub struct VoxGame {
     personal_world: Option<PersonalWorld>,
     renderer: Option<Renderer>,
+    info: WorldInfo,
+    //seconds between autosaves, 0 disables them
+    autosave_interval: u64,
 }
 
 impl VoxGame {
-    pub fn new() -> VoxGame {
+    pub fn new(info: WorldInfo, autosave_interval: u64) -> VoxGame {
         VoxGame {
             personal_world: None,
             renderer: None,
+            info,
+            autosave_interval,
         }
     }
     pub fn run(self) {
@@ -27,18 +33,28 @@ impl VoxGame {
     }
 }
 
+//the main loop also quits without A RenderResult::Exit when the window is closed, saving when the
+//game is dropped covers that as well
+impl Drop for VoxGame {
+    fn drop(&mut self) {
+        if let Some(pw) = self.personal_world.as_mut() {
+            pw.save_and_wait();
+        }
+    }
+}
+
 impl Game for VoxGame {
     fn on_tick(&mut self, dt: f64) -> UpdateResult {
         let pw = &mut self.personal_world.as_mut().unwrap();
         pw.ui
             .debug_info
-            .set_numbers("player x".to_string(), pw.player.position.x as f64);
+            .set_numbers("player x".to_string(), pw.player.position.x);
         pw.ui
             .debug_info
-            .set_numbers("player y".to_string(), pw.player.position.y as f64);
+            .set_numbers("player y".to_string(), pw.player.position.y);
         pw.ui
             .debug_info
-            .set_numbers("player z".to_string(), pw.player.position.z as f64);
+            .set_numbers("player z".to_string(), pw.player.position.z);
         pw.ui.debug_info.set_numbers(
             "amount of renderable chunks".to_string(),
             pw.chunk_render_data.len() as f64,
@@ -47,6 +63,13 @@ impl Game for VoxGame {
             "amount of chunks".to_string(),
             pw.world.count_chunks() as f64,
         );
+        pw.ui
+            .debug_info
+            .set_numbers("world seed".to_string(), pw.world.world_seed as f64);
+        pw.ui.debug_info.set_numbers(
+            "chunk memory (MiB)".to_string(),
+            pw.world.get_memory_usage() as f64 / (1024.0 * 1024.0),
+        );
 
         let timer = Instant::now();
 
@@ -70,8 +93,10 @@ impl Game for VoxGame {
         }
 
         pw.update_ui_input(&input);
-        pw.player.handle_input(&input, &(dt as f32), &pw.world);
+        pw.player.handle_input(&input, &(dt as f32));
+        pw.player.handle_block_input(&input, &mut pw.world);
         if pw.render(&window, self.renderer.as_mut().unwrap()) == RenderResult::Exit {
+            pw.save_and_wait();
             return RenderResult::Exit;
         }
         input.update();
@@ -84,8 +109,13 @@ impl Game for VoxGame {
         RenderResult::Continue
     }
     fn on_init(&mut self, window: &Window) -> InitResult {
-        let renderer = Renderer::new(&window);
-        self.personal_world = Some(PersonalWorld::new(window, &renderer));
+        let mut renderer = Renderer::new(&window);
+        self.personal_world = Some(PersonalWorld::new(
+            window,
+            &mut renderer,
+            &self.info,
+            self.autosave_interval,
+        ));
         self.renderer = Some(renderer);
         return InitResult::Continue;
     }
@@ -1,6 +1,13 @@
 #![allow(dead_code)]
 use crate::game::VoxGame;
 use crate::logger::setup_logger;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use vox_core::constants::DEFAULT_AUTOSAVE_INTERVAL;
+use vox_core::world_config::{set_world_config, WorldConfigOverrides};
+use vox_world::world::world_dir::{set_world_dir, DEFAULT_WORLD_DIR};
+use vox_world::world::world_info::WorldInfo;
 
 mod game;
 mod logger;
@@ -9,6 +16,56 @@ mod ui;
 
 fn main() {
     setup_logger().unwrap();
-    let mut game = VoxGame::new();
+    let overrides = WorldConfigOverrides {
+        chunk_size: get_argument("--chunk-size", "A whole number of blocks"),
+        meta_chunk_size: get_argument("--metachunk-size", "A whole number of chunks"),
+        min_chunk_y: get_argument("--min-chunk-y", "A chunk y"),
+        max_chunk_y: get_argument("--max-chunk-y", "A chunk y"),
+        meta_chunk_gen_range: get_argument("--gen-range", "A whole number of metachunks"),
+        meta_chunk_unload_radius: get_argument("--unload-radius", "A whole number of metachunks"),
+    };
+    let autosave_interval = get_argument("--autosave-interval", "A whole number of seconds")
+        .unwrap_or(DEFAULT_AUTOSAVE_INTERVAL);
+    let world_dir: PathBuf = get_argument("--world", "the path of A world directory")
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_WORLD_DIR));
+    set_world_dir(world_dir).unwrap();
+    let seed = get_argument("--seed", "A whole number");
+    let info = match WorldInfo::load_or_create(seed, overrides) {
+        Ok(info) => info,
+        Err(e) => {
+            println!("couldn't load the world: {}", e);
+            std::process::exit(1);
+        }
+    };
+    //nothing reads the config before this, everything after it sees the dimensions of the world
+    set_world_config(info.config).unwrap();
+    let mut game = VoxGame::new(info, autosave_interval);
     game.run();
 }
+
+//arguments are given as --name <value>. The game doesn't start when A given value can't be used,
+//so A typo doesn't silently fall back to the stored or default value
+fn get_argument<T: FromStr>(name: &str, expected: &str) -> Option<T>
+where
+    T::Err: Display,
+{
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == name)?;
+    let value = match args.get(index + 1) {
+        Some(value) => value,
+        None => {
+            println!("{} needs {}, but no value was given", name, expected);
+            std::process::exit(1);
+        }
+    };
+    return match value.parse::<T>() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!(
+                "{} needs {}, but {:?} is not one: {}",
+                name, expected, value, e
+            );
+            std::process::exit(1);
+        }
+    };
+}
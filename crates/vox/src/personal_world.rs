@@ -6,18 +6,19 @@ use rayon::prelude::ParallelSliceMut;
 use std::cmp::{min, Ordering};
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
-use vox_core::constants::{
-    CHUNKSIZE, METACHUNKSIZE, METACHUNK_GEN_RANGE, METACHUNK_UNLOAD_RADIUS, SEED,
-};
-use vox_core::positions::{ChunkPos, MetaChunkPos};
+use std::time::Instant;
+use vox_core::positions::{ChunkPos, MetaChunkPos, ObjectPos, ToChunkPos};
+use vox_core::world_config::get_world_config;
 use vox_render::renderer::renderer::{resize, Renderer};
 use vox_render::renderer::renderpassable::RenderPassable;
 use vox_render::renderer::wgpu::WgpuState;
 use vox_render::renderer::wgpu_pipeline::WgpuPipeline;
+use vox_world::blocks::block_registry::get_block_registry;
 use vox_world::chunk_render_data::ChunkRenderData;
 use vox_world::player::Player;
 use vox_world::world::small_world::SmallWorld;
+use vox_world::world::voxel_world::VoxelWorld;
+use vox_world::world::world_info::WorldInfo;
 use vox_world::world_gen::chunk_gen_thread::ChunkGenThread;
 use vox_world::world_gen::meta_chunk::MetaChunk;
 use winit::event::Event;
@@ -26,34 +27,60 @@ use winit::window::Window;
 use winit_window_control::input::input::Input;
 use winit_window_control::main_loop::RenderResult;
 
+//seconds per frame spent on building chunk meshes
+const VERTEX_GENERATION_BUDGET: f32 = 0.001;
+
 pub struct PersonalWorld {
     pub world: SmallWorld,
     pub chunk_render_data: HashMap<ChunkPos, ChunkRenderData>,
     pub player: Player,
     pub chunk_gen_thread: ChunkGenThread,
-    pub loading_chunks: HashSet<MetaChunkPos>,
     pub reload_vertex_load_order: bool,
     pub to_generate: Vec<(f32, ChunkPos)>,
+    //chunks with render data which is outdated because blocks changed
+    pub dirty_chunks: HashSet<ChunkPos>,
     pub ui: UiRenderer,
+    //written to the level file every time the world is saved
+    pub info: WorldInfo,
+    //seconds between autosaves, 0 disables them
+    autosave_interval: u64,
+    last_save: Instant,
 }
 
 impl PersonalWorld {
-    pub fn new(window: &Window, renderer: &Renderer) -> PersonalWorld {
+    pub fn new(
+        window: &Window,
+        renderer: &mut Renderer,
+        info: &WorldInfo,
+        autosave_interval: u64,
+    ) -> PersonalWorld {
         let ui_renderer = UiRenderer::new(window, &renderer);
+        let registry = get_block_registry();
+        let main_pipeline = renderer.pipelines.get_mut("main").unwrap();
+        main_pipeline.uniforms.set_colors(registry.get_colors());
+        main_pipeline.set_textures(
+            &renderer.wgpu.device,
+            &renderer.wgpu.queue,
+            registry.get_texture_paths(),
+        );
+        let mut player = Player::new();
+        player.position = ObjectPos {
+            x: info.spawn[0],
+            y: info.spawn[1],
+            z: info.spawn[2],
+        };
         PersonalWorld {
-            world: SmallWorld::new(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as u32,
-            ),
+            world: SmallWorld::new(info.seed, info.time),
             chunk_render_data: HashMap::new(),
-            player: Player::new(),
+            player,
             chunk_gen_thread: ChunkGenThread::new(),
-            loading_chunks: HashSet::new(),
             reload_vertex_load_order: false,
             to_generate: Vec::new(),
+            dirty_chunks: HashSet::new(),
             ui: ui_renderer,
+            info: *info,
+            autosave_interval,
+            last_save: Instant::now(),
         }
     }
     pub fn update(&mut self) {
@@ -63,24 +90,67 @@ impl PersonalWorld {
         self.player.update(&dt, &self.world);
         self.update();
         self.load_generated_chunks();
+        self.mark_changed_chunks_dirty();
         self.to_generate = self.vertex_buffers_to_generate();
-        if self.player.generated_chunks_for != self.player.position.get_chunk()
+        if self.player.generated_chunks_for != self.player.position.get_chunk_pos()
             || self.reload_vertex_load_order
         {
             self.on_player_moved_chunks();
-            self.player.generated_chunks_for = self.player.position.get_chunk();
+            self.player.generated_chunks_for = self.player.position.get_chunk_pos();
             self.reload_vertex_load_order = false;
         }
+        if self.autosave_interval > 0
+            && self.last_save.elapsed().as_secs() >= self.autosave_interval
+        {
+            self.save();
+        }
+    }
+    //hands every modified metachunk to the chunk generation thread, which writes them in the
+    //background, and writes the level file
+    pub fn save(&mut self) {
+        for chunk in self.world.take_modified_meta_chunks() {
+            self.save_meta_chunk(chunk);
+        }
+        self.save_pending_writes();
+        self.info.time = self.world.time;
+        if let Err(e) = self.info.save() {
+            println!("error while saving the level: {}", e);
+        }
+        self.last_save = Instant::now();
+    }
+    //saves and waits until everything is written, used when the game quits
+    pub fn save_and_wait(&mut self) {
+        self.save();
+        self.chunk_gen_thread.finish_saving();
+    }
+    //structure writes into metachunks that aren't loaded are handed to the chunk generation
+    //thread, which writes them to disk without blocking the game
+    fn save_pending_writes(&mut self) {
+        let writes = self.world.take_pending_writes();
+        if writes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.chunk_gen_thread.defer_writes(writes) {
+            println!("error while trying to save deferred writes: {}", e);
+        }
+    }
+    fn save_meta_chunk(&self, chunk: MetaChunk) {
+        if let Err(e) = self.chunk_gen_thread.save(chunk) {
+            println!("error while trying to save A chunk: {}", e);
+        }
     }
 
     pub fn vertex_buffers_to_generate(&self) -> Vec<(f32, ChunkPos)> {
-        let mut to_render = Vec::with_capacity(9 * METACHUNKSIZE * METACHUNKSIZE * METACHUNKSIZE);
+        let config = get_world_config();
+        let mut to_render = Vec::with_capacity(
+            9 * config.meta_chunk_size * config.meta_chunk_size * config.get_height_in_chunks(),
+        );
         for (_, meta_chunk) in self.world.get_all_chunks() {
             for (_, pos) in meta_chunk.get_iter() {
                 let (should_gen, additional_weight) =
                     self.should_generate_vertex_buffers(pos.clone());
                 if should_gen {
-                    let distance = pos.get_distance(&self.player.position.get_chunk());
+                    let distance = pos.get_distance(&self.player.position.get_chunk_pos());
                     to_render.push((distance - additional_weight, pos.clone()));
                 }
             }
@@ -96,7 +166,7 @@ impl PersonalWorld {
         return to_render;
     }
     pub fn should_generate_vertex_buffers(&self, pos: ChunkPos) -> (bool, f32) {
-        let distance = pos.get_distance(&self.player.position.get_chunk());
+        let distance = pos.get_distance(&self.player.position.get_chunk_pos());
         if distance > self.player.render_distance {
             return (false, 0.0);
         }
@@ -104,8 +174,9 @@ impl PersonalWorld {
         if self.world.get_chunk(&pos.get_diff(0, 0, 1)).is_none()
             || self.world.get_chunk(&pos.get_diff(0, 0, -1)).is_none()
             || (self.world.get_chunk(&pos.get_diff(0, 1, 0)).is_none()
-                && pos.y + 1 != METACHUNKSIZE as i32)
-            || (self.world.get_chunk(&pos.get_diff(0, -1, 0)).is_none() && pos.y - 1 >= 0)
+                && get_world_config().contains_chunk_y(pos.y + 1))
+            || (self.world.get_chunk(&pos.get_diff(0, -1, 0)).is_none()
+                && get_world_config().contains_chunk_y(pos.y - 1))
             || self.world.get_chunk(&pos.get_diff(1, 0, 0)).is_none()
             || self.world.get_chunk(&pos.get_diff(-1, 0, 0)).is_none()
         {
@@ -119,36 +190,32 @@ impl PersonalWorld {
             self.player.direction.y,
             self.player.direction.z,
         );
-        let viewer_pos = Vector3::new(
-            self.player.position.x,
-            self.player.position.y,
-            self.player.position.z,
+        let chunk_size = get_world_config().chunk_size as f64;
+        let difference = Vector3::new(
+            (self.player.position.x - pos.x as f64 * chunk_size) as f32,
+            (self.player.position.y - pos.y as f64 * chunk_size) as f32,
+            (self.player.position.z - pos.z as f64 * chunk_size) as f32,
         );
-        let chunk_pos = Vector3::new(
-            (pos.x * CHUNKSIZE as i32) as f32,
-            (pos.y * CHUNKSIZE as i32) as f32,
-            (pos.z * CHUNKSIZE as i32) as f32,
-        );
-        let difference = viewer_pos - chunk_pos;
 
         if view_dir.dot(difference) / (view_dir.magnitude() * difference.magnitude()) < -0.5 {
             return (true, 1000.0);
         }
         return (true, 0.0);
     }
-    pub fn meta_chunk_should_be_loaded(player: &Player, pos: &MetaChunkPos) -> bool {
-        let player_chunk_pos = player.position.get_meta_chunk();
-        pos.x <= player_chunk_pos.x + METACHUNK_UNLOAD_RADIUS as i32
-            && pos.x >= player_chunk_pos.x - METACHUNK_UNLOAD_RADIUS as i32
-            && pos.z <= player_chunk_pos.z + METACHUNK_UNLOAD_RADIUS as i32
-            && pos.z >= player_chunk_pos.z - METACHUNK_UNLOAD_RADIUS as i32
+    pub fn meta_chunk_should_be_loaded(&self, pos: &MetaChunkPos) -> bool {
+        let player_chunk_pos = self.player.position.get_meta_chunk_pos();
+        let radius = get_world_config().meta_chunk_unload_radius as i32;
+        pos.x <= player_chunk_pos.x + radius
+            && pos.x >= player_chunk_pos.x - radius
+            && pos.z <= player_chunk_pos.z + radius
+            && pos.z >= player_chunk_pos.z - radius
     }
     pub fn load_chunk(&mut self, pos: MetaChunkPos) {
         if self.world.chunk_exists_or_generating(&pos) {
             return;
         }
-        self.loading_chunks.insert(pos.clone());
-        let chunk_request_result = self.chunk_gen_thread.request(pos, SEED);
+        self.world.loading_chunks.insert(pos.clone());
+        let chunk_request_result = self.chunk_gen_thread.request(pos, self.world.world_seed);
         match chunk_request_result {
             Ok(_) => (),
             Err(e) => println!("error while trying to load A chunk: {}", e),
@@ -156,25 +223,28 @@ impl PersonalWorld {
     }
     pub fn on_player_moved_chunks(&mut self) {
         self.check_chunks_to_generate();
-        self.world.filter_chunks(&self.player);
+        for chunk in self.world.filter_chunks(&self.player) {
+            self.save_meta_chunk(chunk);
+        }
+        self.save_pending_writes();
         let player = &self.player;
         self.chunk_render_data
             .retain(|pos, _| MetaChunk::retain_meta_chunk(player, pos.get_meta_chunk_pos()));
+        let render_data = &self.chunk_render_data;
+        self.dirty_chunks
+            .retain(|pos| render_data.contains_key(pos));
     }
     pub fn check_chunks_to_generate(&mut self) {
-        let current_chunk = self.player.position.get_meta_chunk();
+        let current_chunk = self.player.position.get_meta_chunk_pos();
         let mut to_load = BinaryHeap::new();
-        for x in current_chunk.x - METACHUNK_GEN_RANGE as i32 - 1
-            ..current_chunk.x + METACHUNK_GEN_RANGE as i32 + 1
-        {
-            for z in current_chunk.z - METACHUNK_GEN_RANGE as i32 - 1
-                ..current_chunk.z + METACHUNK_GEN_RANGE as i32 + 1
-            {
-                if PersonalWorld::meta_chunk_should_be_loaded(&self.player, &MetaChunkPos { x, z })
-                    && !self.loading_chunks.contains(&MetaChunkPos { x, z })
+        let range = get_world_config().meta_chunk_gen_range as i32;
+        for x in current_chunk.x - range - 1..current_chunk.x + range + 1 {
+            for z in current_chunk.z - range - 1..current_chunk.z + range + 1 {
+                if self.meta_chunk_should_be_loaded(&MetaChunkPos { x, z })
+                    && !self.world.loading_chunks.contains(&MetaChunkPos { x, z })
                     && !self
                         .chunk_render_data
-                        .contains_key(&MetaChunkPos { x, z }.get_center_pos().get_chunk())
+                        .contains_key(&MetaChunkPos { x, z }.get_center_pos().get_chunk_pos())
                 {
                     let chunk_pos = MetaChunkPos { x, z };
                     to_load.push((
@@ -188,14 +258,49 @@ impl PersonalWorld {
             self.load_chunk(to_load.pop().unwrap().1);
         }
     }
-    pub fn check_vertices_to_generate(&mut self, renderer: &Renderer) -> i32 {
-        if self.to_generate.is_empty() {
+    //chunks without render data get their mesh built when they are first in range
+    pub fn mark_changed_chunks_dirty(&mut self) {
+        let render_data = &self.chunk_render_data;
+        self.dirty_chunks.extend(
+            self.world
+                .take_changed_chunks()
+                .into_iter()
+                .filter(|pos| render_data.contains_key(pos)),
+        );
+    }
+    //rebuilds the meshes of changed chunks, closest to the player first
+    pub fn remesh_dirty_chunks(&mut self, renderer: &Renderer, lag_timer: &Instant) -> i32 {
+        if self.dirty_chunks.is_empty() {
             return 0;
         }
+        let player_chunk = self.player.position.get_chunk_pos();
+        let mut dirty: Vec<ChunkPos> = self.dirty_chunks.iter().cloned().collect();
+        dirty.sort_unstable_by_key(|pos| (pos.get_distance(&player_chunk) * 1000f32) as i32);
+        let mut remeshed = 0;
+        for pos in dirty {
+            if lag_timer.elapsed().as_secs_f32() >= VERTEX_GENERATION_BUDGET && remeshed > 0 {
+                break;
+            }
+            self.dirty_chunks.remove(&pos);
+            if self.chunk_render_data.contains_key(&pos) {
+                let data = ChunkRenderData::new(&self.world, &pos, &renderer.wgpu.device);
+                self.chunk_render_data.insert(pos, data);
+                remeshed += 1;
+            }
+        }
+        return remeshed;
+    }
+    pub fn check_vertices_to_generate(&mut self, renderer: &Renderer) -> i32 {
         let lag_timer = Instant::now();
+        let remeshed = self.remesh_dirty_chunks(renderer, &lag_timer);
+        if self.to_generate.is_empty() {
+            return remeshed;
+        }
         //println!("started generating vertices");
         let starting_size = self.to_generate.len();
-        while lag_timer.elapsed().as_secs_f32() < 0.001 && !self.to_generate.is_empty() {
+        while lag_timer.elapsed().as_secs_f32() < VERTEX_GENERATION_BUDGET
+            && !self.to_generate.is_empty()
+        {
             let len = self.to_generate.len();
             if len > 0 {
                 let (_, pos) = &self.to_generate[self.to_generate.len() - 1];
@@ -209,13 +314,12 @@ impl PersonalWorld {
             starting_size - self.to_generate.len(),
             lag_timer.elapsed().as_secs_f32()
         );*/
-        return (starting_size - self.to_generate.len()) as i32;
+        return remeshed + (starting_size - self.to_generate.len()) as i32;
     }
     pub fn load_generated_chunks(&mut self) {
         let message = self.chunk_gen_thread.get();
         match message {
             Ok((chunk, pos)) => {
-                self.loading_chunks.remove(&pos);
                 self.world.add_chunk(pos, chunk);
                 self.reload_vertex_load_order = true;
             }
@@ -227,12 +331,11 @@ impl PersonalWorld {
     }
     pub fn render(&mut self, window: &Window, renderer: &mut Renderer) -> RenderResult {
         let main_pipeline = renderer.pipelines.get_mut("main").unwrap();
+        let camera_pos = self.player.get_camera_pos();
+        let camera_chunk = camera_pos.get_chunk_pos();
         main_pipeline.uniforms.update_view_proj(
-            [
-                self.player.position.x,
-                self.player.position.y,
-                self.player.position.z,
-            ],
+            [camera_chunk.x, camera_chunk.y, camera_chunk.z],
+            camera_pos.get_offset_in_chunk(),
             self.player.get_view_matrix(),
             (renderer.wgpu.size.width, renderer.wgpu.size.height),
             self.world.time,
@@ -1,25 +1,13 @@
-pub const CHUNKSIZE: usize = 32;
+//defaults of the world config
+pub const DEFAULT_CHUNKSIZE: usize = 32;
+pub const DEFAULT_METACHUNKSIZE: usize = 8;
+pub const DEFAULT_METACHUNK_GEN_RANGE: usize = 2;
+pub const DEFAULT_METACHUNK_UNLOAD_RADIUS: usize = 3;
+pub const DEFAULT_MIN_CHUNK_Y: i32 = 0;
+pub const DEFAULT_MAX_CHUNK_Y: i32 = 8;
+//seconds between saves of the modified metachunks, 0 only saves on unload and exit
+pub const DEFAULT_AUTOSAVE_INTERVAL: u64 = 60;
 pub const WIDTH: usize = 1280;
 pub const HEIGHT: usize = 720;
-pub const METACHUNK_GEN_RANGE: usize = 2;
-pub const METACHUNK_UNLOAD_RADIUS: usize = 3;
-pub const METACHUNKSIZE: usize = 8;
-pub const SEED: u32 = 0;
-pub const COLORS: [[f32; 4]; 16] = [
-    [0.0, 255.0, 0.0, 255.0],
-    [0.0, 0.0, 255.0, 128.0],
-    [255.0, 64.0, 64.0, 255.0],
-    [128.0, 128.0, 128.0, 255.0],
-    [255.0, 0.0, 0.0, 255.0],
-    [255.0, 0.0, 255.0, 0.0],
-    [0.0, 128.0, 0.0, 254.99],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-    [0.0; 4],
-];
+//amount of block colors uploaded to the shaders, one for every possible block id
+pub const BLOCK_COLOR_COUNT: usize = 256;
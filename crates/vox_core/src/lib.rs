@@ -1,3 +1,4 @@
 pub mod constants;
 pub mod positions;
 pub mod utils;
+pub mod world_config;
@@ -1,10 +1,9 @@
-use crate::constants::{CHUNKSIZE, METACHUNKSIZE};
-use crate::utils::{wrap, wrapf};
+use crate::world_config::{get_chunk_size, get_meta_chunk_size, get_world_config};
 use core::ops;
 use num_traits::Pow;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct GlobalBlockPos {
     pub x: i32,
     pub y: i32,
@@ -17,12 +16,12 @@ pub struct LocalBlockPos {
     pub y: i32,
     pub z: i32,
 }
-//position of entities
+//position of entities, double precision so it stays exact far away from the origin
 #[derive(Debug, PartialEq)]
 pub struct ObjectPos {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
 }
 //position of global chunk
 #[derive(Hash, PartialEq, Debug, Clone, PartialOrd, Ord)]
@@ -37,7 +36,7 @@ pub struct MetaChunkPos {
     pub x: i32,
     pub z: i32,
 }
-//chunk pos within A metachunk
+//chunk pos within A metachunk, y counts up from the lowest chunk of the world
 #[derive(Debug, PartialEq, Eq)]
 pub struct LocalChunkPos {
     pub x: i32,
@@ -45,12 +44,99 @@ pub struct LocalChunkPos {
     pub z: i32,
 }
 
+//positions that lie inside A block. Every other block based position is derived from the
+//global block, using euclidean division so negative coordinates round down as well
+pub trait ToBlockPos {
+    fn get_global_block_pos(&self) -> GlobalBlockPos;
+
+    fn get_local_pos(&self) -> LocalBlockPos {
+        let pos = self.get_global_block_pos();
+        let size = get_chunk_size() as i32;
+        LocalBlockPos {
+            x: pos.x.rem_euclid(size),
+            y: pos.y.rem_euclid(size),
+            z: pos.z.rem_euclid(size),
+        }
+    }
+}
+
+//positions that lie inside A chunk, the local chunk and metachunk are derived from the chunk
+pub trait ToChunkPos {
+    fn get_chunk_pos(&self) -> ChunkPos;
+
+    fn get_local_chunk_pos(&self) -> LocalChunkPos {
+        let pos = self.get_chunk_pos();
+        let size = get_meta_chunk_size() as i32;
+        LocalChunkPos {
+            x: pos.x.rem_euclid(size),
+            y: pos.y - get_world_config().min_chunk_y,
+            z: pos.z.rem_euclid(size),
+        }
+    }
+    fn get_meta_chunk_pos(&self) -> MetaChunkPos {
+        let pos = self.get_chunk_pos();
+        let size = get_meta_chunk_size() as i32;
+        MetaChunkPos {
+            x: pos.x.div_euclid(size),
+            z: pos.z.div_euclid(size),
+        }
+    }
+}
+
+impl ToBlockPos for GlobalBlockPos {
+    fn get_global_block_pos(&self) -> GlobalBlockPos {
+        *self
+    }
+}
+impl ToBlockPos for ObjectPos {
+    fn get_global_block_pos(&self) -> GlobalBlockPos {
+        GlobalBlockPos {
+            x: self.x.floor() as i32,
+            y: self.y.floor() as i32,
+            z: self.z.floor() as i32,
+        }
+    }
+}
+impl ToChunkPos for GlobalBlockPos {
+    fn get_chunk_pos(&self) -> ChunkPos {
+        let size = get_chunk_size() as i32;
+        ChunkPos {
+            x: self.x.div_euclid(size),
+            y: self.y.div_euclid(size),
+            z: self.z.div_euclid(size),
+        }
+    }
+}
+impl ToChunkPos for ObjectPos {
+    fn get_chunk_pos(&self) -> ChunkPos {
+        self.get_global_block_pos().get_chunk_pos()
+    }
+}
+impl ToChunkPos for ChunkPos {
+    fn get_chunk_pos(&self) -> ChunkPos {
+        self.clone()
+    }
+}
+
+impl LocalBlockPos {
+    //the inverse of get_chunk_pos and get_local_pos
+    pub fn get_global_pos(&self, chunk: &ChunkPos) -> GlobalBlockPos {
+        let size = get_chunk_size() as i32;
+        GlobalBlockPos {
+            x: chunk.x * size + self.x,
+            y: chunk.y * size + self.y,
+            z: chunk.z * size + self.z,
+        }
+    }
+}
+
 impl LocalChunkPos {
+    //the inverse of get_meta_chunk_pos and get_local_chunk_pos
     pub fn get_chunk_pos(&self, pos: &MetaChunkPos) -> ChunkPos {
         ChunkPos {
-            x: pos.x * METACHUNKSIZE as i32 + self.x,
-            y: self.y,
-            z: pos.z * METACHUNKSIZE as i32 + self.z,
+            x: pos.x * get_meta_chunk_size() as i32 + self.x,
+            y: self.y + get_world_config().min_chunk_y,
+            z: pos.z * get_meta_chunk_size() as i32 + self.z,
         }
     }
 }
@@ -58,13 +144,6 @@ impl LocalChunkPos {
 impl Eq for ChunkPos {}
 
 impl GlobalBlockPos {
-    pub fn get_local_chunk(&self) -> LocalChunkPos {
-        LocalChunkPos {
-            x: wrapf(self.x as f32 / (CHUNKSIZE as f32), METACHUNKSIZE as f32).floor() as i32,
-            y: wrapf(self.y as f32 / (CHUNKSIZE as f32), METACHUNKSIZE as f32).floor() as i32,
-            z: wrapf(self.z as f32 / (CHUNKSIZE as f32), METACHUNKSIZE as f32).floor() as i32,
-        }
-    }
     pub fn get_diff(&self, x_diff: i32, y_diff: i32, z_diff: i32) -> GlobalBlockPos {
         GlobalBlockPos {
             x: self.x + x_diff,
@@ -72,39 +151,40 @@ impl GlobalBlockPos {
             z: self.z + z_diff,
         }
     }
-    pub fn get_local_pos(&self) -> LocalBlockPos {
-        LocalBlockPos {
-            x: wrap(self.x, CHUNKSIZE as i32),
-            y: wrap(self.y, CHUNKSIZE as i32),
-            z: wrap(self.z, CHUNKSIZE as i32),
-        }
-    }
-    pub fn get_chunk_pos(&self) -> ChunkPos {
-        ChunkPos {
-            x: (self.x as f32 / CHUNKSIZE as f32).floor() as i32,
-            y: (self.y as f32 / CHUNKSIZE as f32).floor() as i32,
-            z: (self.z as f32 / CHUNKSIZE as f32).floor() as i32,
-        }
-    }
     pub fn get_block_centre(&self) -> ObjectPos {
         ObjectPos {
-            x: self.x as f32 + 0.5,
-            y: self.y as f32 + 0.5,
-            z: self.z as f32 + 0.5,
+            x: self.x as f64 + 0.5,
+            y: self.y as f64 + 0.5,
+            z: self.z as f64 + 0.5,
         }
     }
     pub fn get_block_pos(&self) -> ObjectPos {
         ObjectPos {
-            x: self.x as f32,
-            y: self.y as f32,
-            z: self.z as f32,
+            x: self.x as f64,
+            y: self.y as f64,
+            z: self.z as f64,
         }
     }
-    pub fn get_meta_chunk_pos(&self) -> MetaChunkPos {
-        MetaChunkPos {
-            x: (self.x as f32 / (CHUNKSIZE as f32 * METACHUNKSIZE as f32)).floor() as i32,
-            z: (self.z as f32 / (CHUNKSIZE as f32 * METACHUNKSIZE as f32)).floor() as i32,
+    //the chunk of the block, and every neighboring chunk the block touches
+    pub fn get_affected_chunks(&self) -> Vec<ChunkPos> {
+        let chunk_pos = self.get_chunk_pos();
+        let local_pos = self.get_local_pos();
+        let last = get_chunk_size() as i32 - 1;
+        let borders = [
+            (local_pos.x == 0, chunk_pos.get_diff(-1, 0, 0)),
+            (local_pos.x == last, chunk_pos.get_diff(1, 0, 0)),
+            (local_pos.y == 0, chunk_pos.get_diff(0, -1, 0)),
+            (local_pos.y == last, chunk_pos.get_diff(0, 1, 0)),
+            (local_pos.z == 0, chunk_pos.get_diff(0, 0, -1)),
+            (local_pos.z == last, chunk_pos.get_diff(0, 0, 1)),
+        ];
+        let mut chunks = vec![chunk_pos.clone()];
+        for (on_border, neighbor) in borders.iter() {
+            if *on_border {
+                chunks.push(neighbor.clone());
+            }
         }
+        return chunks;
     }
 }
 impl ChunkPos {
@@ -124,26 +204,13 @@ impl ChunkPos {
             + ((self.y - pos.y) as f32).pow(2)
             + ((self.z - pos.z) as f32).pow(2)) as f32)
             .sqrt()
-            * CHUNKSIZE as f32
-    }
-    pub fn get_local_chunk_pos(&self) -> LocalChunkPos {
-        LocalChunkPos {
-            x: wrap(self.x, METACHUNKSIZE as i32),
-            y: wrap(self.y, METACHUNKSIZE as i32),
-            z: wrap(self.z, METACHUNKSIZE as i32),
-        }
-    }
-    pub fn get_meta_chunk_pos(&self) -> MetaChunkPos {
-        MetaChunkPos {
-            x: (self.x as f32 / METACHUNKSIZE as f32).floor() as i32,
-            z: (self.z as f32 / METACHUNKSIZE as f32).floor() as i32,
-        }
+            * get_chunk_size() as f32
     }
     pub fn get_center_pos(&self) -> ObjectPos {
         ObjectPos {
-            x: self.x as f32 * CHUNKSIZE as f32 + CHUNKSIZE as f32 / 2.0,
-            y: self.y as f32 * CHUNKSIZE as f32 + CHUNKSIZE as f32 / 2.0,
-            z: self.z as f32 * CHUNKSIZE as f32 + CHUNKSIZE as f32 / 2.0,
+            x: self.x as f64 * get_chunk_size() as f64 + get_chunk_size() as f64 / 2.0,
+            y: self.y as f64 * get_chunk_size() as f64 + get_chunk_size() as f64 / 2.0,
+            z: self.z as f64 * get_chunk_size() as f64 + get_chunk_size() as f64 / 2.0,
         }
     }
 }
@@ -157,46 +224,34 @@ impl MetaChunkPos {
     }
     pub fn get_distance_to_object(&self, pos: &ObjectPos) -> f32 {
         let center_pos = self.get_center_pos();
-        ((center_pos.x - pos.x).pow(2) as f32 + (center_pos.z - pos.z).pow(2) as f32).sqrt()
+        ((center_pos.x - pos.x).powi(2) + (center_pos.z - pos.z).powi(2)).sqrt() as f32
     }
     pub fn get_center_pos(&self) -> ObjectPos {
+        let size = get_world_config().get_meta_chunk_blocks() as f64;
         ObjectPos {
-            x: self.x as f32 * METACHUNKSIZE as f32 * CHUNKSIZE as f32 + METACHUNKSIZE as f32 / 2.0,
-            y: 0f32,
-            z: self.z as f32 * METACHUNKSIZE as f32 * CHUNKSIZE as f32 + METACHUNKSIZE as f32 / 2.0,
+            x: self.x as f64 * size + size / 2.0,
+            y: 0f64,
+            z: self.z as f64 * size + size / 2.0,
         }
     }
 }
 
 impl ObjectPos {
-    pub fn get_block(&self) -> GlobalBlockPos {
-        GlobalBlockPos {
-            x: self.x as i32,
-            y: self.y as i32,
-            z: self.z as i32,
-        }
-    }
-
-    pub fn get_chunk(&self) -> ChunkPos {
-        ChunkPos {
-            x: self.x as i32 / CHUNKSIZE as i32,
-            y: self.y as i32 / CHUNKSIZE as i32,
-            z: self.z as i32 / CHUNKSIZE as i32,
-        }
-    }
     pub fn get_distance(&self, pos: &ObjectPos) -> f32 {
-        ((self.x - pos.x).pow(2) as f32
-            + (self.y - pos.y).pow(2) as f32
-            + (self.z - pos.z).pow(2) as f32)
-            .sqrt()
+        ((self.x - pos.x).powi(2) + (self.y - pos.y).powi(2) + (self.z - pos.z).powi(2)).sqrt()
+            as f32
     }
-    pub fn get_meta_chunk(&self) -> MetaChunkPos {
-        MetaChunkPos {
-            x: self.x as i32 / (CHUNKSIZE as i32 * METACHUNKSIZE as i32),
-            z: self.z as i32 / (CHUNKSIZE as i32 * METACHUNKSIZE as i32),
-        }
+    //position relative to the lowest corner of the chunk it is in, small enough for f32
+    pub fn get_offset_in_chunk(&self) -> [f32; 3] {
+        let chunk = self.get_chunk_pos();
+        let size = get_chunk_size() as f64;
+        [
+            (self.x - chunk.x as f64 * size) as f32,
+            (self.y - chunk.y as f64 * size) as f32,
+            (self.z - chunk.z as f64 * size) as f32,
+        ]
     }
-    pub fn get_diff(&self, x_diff: f32, y_diff: f32, z_diff: f32) -> ObjectPos {
+    pub fn get_diff(&self, x_diff: f64, y_diff: f64, z_diff: f64) -> ObjectPos {
         ObjectPos {
             x: (self.x + x_diff),
             y: (self.y + y_diff),
@@ -225,3 +280,175 @@ impl ops::Sub<i32> for GlobalBlockPos {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 10000;
+
+    //xorshift, so every run checks the same positions
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        //mostly small coordinates around the origin, sometimes anything an i32 can hold
+        fn next_coordinate(&mut self) -> i32 {
+            let value = self.next_u64();
+            if value & 3 == 0 {
+                value as i32
+            } else {
+                (value >> 32) as i32 % 100_000
+            }
+        }
+    }
+
+    //coordinates where rounding goes wrong first
+    fn get_edge_cases() -> Vec<i32> {
+        let size = get_chunk_size() as i32;
+        let meta_size = get_meta_chunk_size() as i32 * size;
+        vec![
+            0,
+            1,
+            -1,
+            size - 1,
+            size,
+            -size,
+            -size - 1,
+            -size + 1,
+            meta_size,
+            -meta_size,
+            -meta_size - 1,
+            i32::MAX,
+            i32::MIN,
+            i32::MAX - size,
+            i32::MIN + size,
+        ]
+    }
+
+    fn get_block_positions() -> Vec<GlobalBlockPos> {
+        let edges = get_edge_cases();
+        let mut positions = Vec::new();
+        for &x in edges.iter() {
+            for &y in edges.iter() {
+                positions.push(GlobalBlockPos {
+                    x,
+                    y,
+                    z: x.wrapping_neg(),
+                });
+            }
+        }
+        let mut rng = TestRng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..SAMPLES {
+            positions.push(GlobalBlockPos {
+                x: rng.next_coordinate(),
+                y: rng.next_coordinate(),
+                z: rng.next_coordinate(),
+            });
+        }
+        return positions;
+    }
+
+    fn assert_inside(value: i32, start: i64, size: i64) {
+        let value = value as i64;
+        assert!(
+            start <= value && value < start + size,
+            "{} isn't in {}..{}",
+            value,
+            start,
+            start + size
+        );
+    }
+
+    #[test]
+    fn block_positions_round_trip_through_chunks() {
+        let size = get_chunk_size() as i64;
+        for pos in get_block_positions() {
+            let chunk = pos.get_chunk_pos();
+            let local = pos.get_local_pos();
+            for &(value, local_value, chunk_value) in [
+                (pos.x, local.x, chunk.x),
+                (pos.y, local.y, chunk.y),
+                (pos.z, local.z, chunk.z),
+            ]
+            .iter()
+            {
+                assert_inside(local_value, 0, size);
+                assert_inside(value, chunk_value as i64 * size, size);
+            }
+            assert_eq!(local.get_global_pos(&chunk), pos);
+        }
+    }
+
+    #[test]
+    fn chunk_positions_round_trip_through_meta_chunks() {
+        let size = get_meta_chunk_size() as i64;
+        let min_chunk_y = get_world_config().min_chunk_y;
+        for block in get_block_positions() {
+            let chunk = block.get_chunk_pos();
+            let meta_chunk = chunk.get_meta_chunk_pos();
+            let local = chunk.get_local_chunk_pos();
+            assert_inside(local.x, 0, size);
+            assert_inside(local.z, 0, size);
+            assert_inside(chunk.x, meta_chunk.x as i64 * size, size);
+            assert_inside(chunk.z, meta_chunk.z as i64 * size, size);
+            assert_eq!(local.y as i64, chunk.y as i64 - min_chunk_y as i64);
+            assert_eq!(block.get_meta_chunk_pos(), meta_chunk);
+            assert_eq!(local.get_chunk_pos(&meta_chunk), chunk);
+        }
+    }
+
+    #[test]
+    fn object_positions_round_down_to_their_block() {
+        let mut rng = TestRng(0xD1B5_4A32_D192_ED03);
+        let mut positions: Vec<ObjectPos> = get_edge_cases()
+            .iter()
+            .flat_map(|&v| {
+                let v = v as f64;
+                vec![
+                    ObjectPos { x: v, y: v, z: v },
+                    ObjectPos {
+                        x: v + 0.5,
+                        y: v - 0.5,
+                        z: v + 0.999,
+                    },
+                ]
+            })
+            .filter(|pos| pos.x < i32::MAX as f64 && pos.y >= i32::MIN as f64)
+            .collect();
+        for _ in 0..SAMPLES {
+            let fraction = (rng.next_u64() % 1000) as f64 / 1000.0;
+            positions.push(ObjectPos {
+                x: rng.next_coordinate() as f64 + fraction,
+                y: rng.next_coordinate() as f64 - fraction,
+                z: rng.next_coordinate() as f64 + fraction,
+            });
+        }
+        for pos in positions {
+            let block = pos.get_global_block_pos();
+            assert_eq!(block.x as f64, pos.x.floor());
+            assert_eq!(block.y as f64, pos.y.floor());
+            assert_eq!(block.z as f64, pos.z.floor());
+            assert_eq!(pos.get_chunk_pos(), block.get_chunk_pos());
+            assert_eq!(pos.get_meta_chunk_pos(), block.get_meta_chunk_pos());
+            assert_eq!(pos.get_local_pos(), block.get_local_pos());
+        }
+    }
+
+    #[test]
+    fn meta_chunk_centers_lie_in_their_meta_chunk() {
+        let blocks = get_world_config().get_meta_chunk_blocks() as f64;
+        for &(x, z) in [(0, 0), (-1, 0), (3, -7), (-100, 250)].iter() {
+            let pos = MetaChunkPos { x, z };
+            let center = pos.get_center_pos();
+            assert_eq!(center.get_meta_chunk_pos(), pos);
+            assert_eq!(center.x, x as f64 * blocks + blocks / 2.0);
+            assert_eq!(center.z, z as f64 * blocks + blocks / 2.0);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::constants::{
+    DEFAULT_CHUNKSIZE, DEFAULT_MAX_CHUNK_Y, DEFAULT_METACHUNKSIZE, DEFAULT_METACHUNK_GEN_RANGE,
+    DEFAULT_METACHUNK_UNLOAD_RADIUS, DEFAULT_MIN_CHUNK_Y,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+//the config of the world being played and the only one chunks, positions and worlds read. It is
+//installed from the level file before the first chunk exists, so A process can only play worlds
+//with A single set of dimensions. Without A world, like in tests, the defaults are used
+static WORLD_CONFIG: OnceLock<WorldConfig> = OnceLock::new();
+
+//dimensions of A world, stored with the world and chosen when it is created
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WorldConfig {
+    //blocks along every side of A chunk
+    pub chunk_size: usize,
+    //chunks along the x and z side of A metachunk
+    pub meta_chunk_size: usize,
+    //lowest chunk y of the world, can be below 0
+    pub min_chunk_y: i32,
+    //chunk y above the highest chunk of the world, metachunks span from min_chunk_y up to this.
+    //Every metachunk holds the whole height, so A big range costs memory everywhere
+    pub max_chunk_y: i32,
+    //metachunks around the player which get generated
+    pub meta_chunk_gen_range: usize,
+    //metachunks further away from the player than this get unloaded
+    pub meta_chunk_unload_radius: usize,
+}
+
+//values given on the command line, everything that isn't given is taken from the stored world or,
+//for A new world, from the defaults
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct WorldConfigOverrides {
+    pub chunk_size: Option<usize>,
+    pub meta_chunk_size: Option<usize>,
+    pub min_chunk_y: Option<i32>,
+    pub max_chunk_y: Option<i32>,
+    pub meta_chunk_gen_range: Option<usize>,
+    pub meta_chunk_unload_radius: Option<usize>,
+}
+
+impl Default for WorldConfig {
+    fn default() -> WorldConfig {
+        WorldConfig::new()
+    }
+}
+
+impl WorldConfig {
+    pub fn new() -> WorldConfig {
+        WorldConfig {
+            chunk_size: DEFAULT_CHUNKSIZE,
+            meta_chunk_size: DEFAULT_METACHUNKSIZE,
+            min_chunk_y: DEFAULT_MIN_CHUNK_Y,
+            max_chunk_y: DEFAULT_MAX_CHUNK_Y,
+            meta_chunk_gen_range: DEFAULT_METACHUNK_GEN_RANGE,
+            meta_chunk_unload_radius: DEFAULT_METACHUNK_UNLOAD_RADIUS,
+        }
+    }
+    pub fn get_chunk_volume(&self) -> usize {
+        self.chunk_size * self.chunk_size * self.chunk_size
+    }
+    //amount of blocks along the x and z side of A metachunk
+    pub fn get_meta_chunk_blocks(&self) -> usize {
+        self.meta_chunk_size * self.chunk_size
+    }
+    pub fn get_height_in_chunks(&self) -> usize {
+        (self.max_chunk_y - self.min_chunk_y).max(0) as usize
+    }
+    pub fn get_height(&self) -> usize {
+        self.get_height_in_chunks() * self.chunk_size
+    }
+    //lowest block y of the world
+    pub fn get_min_y(&self) -> i32 {
+        self.min_chunk_y * self.chunk_size as i32
+    }
+    //block y above the highest block of the world
+    pub fn get_max_y(&self) -> i32 {
+        self.max_chunk_y * self.chunk_size as i32
+    }
+    pub fn contains_chunk_y(&self, y: i32) -> bool {
+        y >= self.min_chunk_y && y < self.max_chunk_y
+    }
+    //only the dimensions decide how saved chunks are laid out, the ranges may differ between runs
+    pub fn is_compatible(&self, other: &WorldConfig) -> bool {
+        self.chunk_size == other.chunk_size
+            && self.meta_chunk_size == other.meta_chunk_size
+            && self.min_chunk_y == other.min_chunk_y
+            && self.max_chunk_y == other.max_chunk_y
+    }
+}
+
+impl WorldConfigOverrides {
+    pub fn apply_to(&self, config: WorldConfig) -> WorldConfig {
+        WorldConfig {
+            chunk_size: self.chunk_size.unwrap_or(config.chunk_size),
+            meta_chunk_size: self.meta_chunk_size.unwrap_or(config.meta_chunk_size),
+            min_chunk_y: self.min_chunk_y.unwrap_or(config.min_chunk_y),
+            max_chunk_y: self.max_chunk_y.unwrap_or(config.max_chunk_y),
+            meta_chunk_gen_range: self
+                .meta_chunk_gen_range
+                .unwrap_or(config.meta_chunk_gen_range),
+            meta_chunk_unload_radius: self
+                .meta_chunk_unload_radius
+                .unwrap_or(config.meta_chunk_unload_radius),
+        }
+    }
+}
+
+//installs the config used by the whole game, this only works before the config is first used
+pub fn set_world_config(config: WorldConfig) -> Result<(), WorldConfig> {
+    WORLD_CONFIG.set(config)
+}
+
+#[inline]
+pub fn get_world_config() -> &'static WorldConfig {
+    WORLD_CONFIG.get_or_init(WorldConfig::new)
+}
+#[inline]
+pub fn get_chunk_size() -> usize {
+    get_world_config().chunk_size
+}
+#[inline]
+pub fn get_meta_chunk_size() -> usize {
+    get_world_config().meta_chunk_size
+}
+#[inline]
+pub fn get_meta_chunk_gen_range() -> usize {
+    get_world_config().meta_chunk_gen_range
+}
+#[inline]
+pub fn get_meta_chunk_unload_radius() -> usize {
+    get_world_config().meta_chunk_unload_radius
+}
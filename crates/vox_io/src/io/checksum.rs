@@ -0,0 +1,55 @@
+use crate::io::error::IoError;
+use std::sync::OnceLock;
+
+//bytes of the checksum placed in front of every payload
+pub const CHECKSUM_BYTES: usize = 4;
+
+static CRC_TABLE: OnceLock<[u32; 256]> = OnceLock::new();
+
+//CRC-32 as used by zip and png
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let table = CRC_TABLE.get_or_init(|| {
+        let mut table = [0u32; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut c = i as u32;
+            for _ in 0..8 {
+                c = if c & 1 == 1 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+            }
+            *entry = c;
+        }
+        table
+    });
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc = table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+//the payload with its checksum in front of it
+pub fn add_checksum(payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(CHECKSUM_BYTES + payload.len());
+    bytes.extend_from_slice(&crc32(payload).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    return bytes;
+}
+
+//the payload behind the checksum, if it matches
+pub fn check_checksum(bytes: &[u8]) -> Result<&[u8], IoError> {
+    if bytes.len() < CHECKSUM_BYTES {
+        return Err(IoError::Truncated);
+    }
+    let mut stored = [0u8; CHECKSUM_BYTES];
+    stored.copy_from_slice(&bytes[..CHECKSUM_BYTES]);
+    let stored = u32::from_le_bytes(stored);
+    let payload = &bytes[CHECKSUM_BYTES..];
+    let computed = crc32(payload);
+    if stored != computed {
+        return Err(IoError::ChecksumMismatch { stored, computed });
+    }
+    return Ok(payload);
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum IoError {
+    Io(std::io::Error),
+    //the data doesn't match the checksum stored with it
+    ChecksumMismatch { stored: u32, computed: u32 },
+    //the data ends before its header, or A region table, says it should
+    Truncated,
+    //doesn't start with the magic number, so it wasn't written by vox_io
+    BadMagic,
+    //written by A newer version of the game
+    UnsupportedVersion { found: u32, supported: u32 },
+    //no migration is registered to upgrade from this version
+    MissingMigration { from: u32 },
+    Serialize(bincode::Error),
+    Deserialize(bincode::Error),
+}
+
+impl IoError {
+    //the data on disk is damaged, as opposed to the disk not being accessible
+    pub fn is_corrupt(&self) -> bool {
+        return match self {
+            IoError::ChecksumMismatch { .. }
+            | IoError::Truncated
+            | IoError::BadMagic
+            | IoError::Deserialize(_) => true,
+            IoError::Io(_)
+            | IoError::UnsupportedVersion { .. }
+            | IoError::MissingMigration { .. }
+            | IoError::Serialize(_) => false,
+        };
+    }
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IoError::Io(e) => write!(f, "{}", e),
+            IoError::ChecksumMismatch { stored, computed } => write!(
+                f,
+                "checksum mismatch, stored {:08x} but the data has {:08x}",
+                stored, computed
+            ),
+            IoError::Truncated => write!(f, "the data is truncated"),
+            IoError::BadMagic => write!(f, "not A vox save file"),
+            IoError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "format version {} is newer than the supported version {}",
+                found, supported
+            ),
+            IoError::MissingMigration { from } => {
+                write!(f, "no migration from format version {}", from)
+            }
+            IoError::Serialize(e) => write!(f, "couldn't serialize: {}", e),
+            IoError::Deserialize(e) => write!(f, "couldn't deserialize: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IoError {}
+
+impl From<std::io::Error> for IoError {
+    fn from(e: std::io::Error) -> IoError {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            return IoError::Truncated;
+        }
+        return IoError::Io(e);
+    }
+}
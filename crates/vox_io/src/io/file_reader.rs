@@ -1,13 +1,14 @@
-use serde::Deserialize;
-use std::fs::File;
-use std::io::BufReader;
+use crate::io::error::IoError;
+use crate::io::format::{decode, SaveFormat};
+use std::io::ErrorKind;
+use std::path::Path;
 
-pub fn read_meta_chunk_from_file<T: for<'de> Deserialize<'de>>(filename: &str) -> Option<T> {
-    let f = File::open(filename);
-    if f.is_ok() {
-        let reader = BufReader::new(f.unwrap());
-        let obj = bincode::deserialize_from(reader).unwrap();
-        return Some(obj);
-    }
-    return None;
+//reads any file written with write_to_file, None if the file doesn't exist
+pub fn read_save_file<T: SaveFormat>(filename: impl AsRef<Path>) -> Result<Option<T>, IoError> {
+    let bytes = match std::fs::read(filename) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    return Ok(Some(decode(&bytes)?));
 }
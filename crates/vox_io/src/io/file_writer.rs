@@ -1,9 +1,33 @@
-use serde::Serialize;
+use crate::io::error::IoError;
+use crate::io::format::{encode, SaveFormat};
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-pub fn write_to_file<T: Serialize>(filename: &str, obj: &T) {
-    let file = File::create(filename).unwrap();
-    let writer = BufWriter::new(file);
-    bincode::serialize_into(writer, obj).unwrap();
+//the object is written with its header, see write_atomically
+pub fn write_to_file<T: SaveFormat>(filename: impl AsRef<Path>, obj: &T) -> Result<(), IoError> {
+    return write_atomically(filename.as_ref(), &encode(obj)?);
+}
+
+//writes to A temporary file next to the target and renames it over the target once everything is
+//on disk, so A crash leaves either the old or the new file behind, never A part of one
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), IoError> {
+    let temp_path = get_temp_path(path);
+    let result = (|| -> Result<(), IoError> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)?;
+        return Ok(());
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    return result;
+}
+
+fn get_temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    return path.with_file_name(name);
 }
@@ -0,0 +1,174 @@
+use crate::io::checksum::{add_checksum, check_checksum};
+use crate::io::error::IoError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+//everything vox_io writes starts with this, followed by A checksum of the rest. The rest is the
+//format version of the type as A little endian u32 and then the bincode of the object. Every type
+//starts at version 1, nothing without the header was ever saved
+pub const MAGIC: [u8; 4] = *b"VOXS";
+const VERSION_BYTES: usize = 4;
+
+//A type that can be saved. Whenever its serialized layout changes, FORMAT_VERSION is increased
+//and A migration from the previous version is registered, so older saves keep loading
+pub trait SaveFormat: Serialize + for<'de> Deserialize<'de> {
+    const FORMAT_VERSION: u32;
+    fn get_migrations() -> MigrationRegistry {
+        return MigrationRegistry::new();
+    }
+}
+
+//upgrades A payload by one version
+pub type Migration = fn(Vec<u8>) -> Result<Vec<u8>, IoError>;
+
+#[derive(Default)]
+pub struct MigrationRegistry {
+    //keyed by the version A migration upgrades from
+    steps: HashMap<u32, Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> MigrationRegistry {
+        MigrationRegistry {
+            steps: HashMap::new(),
+        }
+    }
+    //the migration turns A payload of version from into one of version from + 1
+    pub fn register(mut self, from: u32, migration: Migration) -> MigrationRegistry {
+        self.steps.insert(from, migration);
+        return self;
+    }
+    //applies every migration from the given version up to the target, one version at A time
+    pub fn migrate(&self, mut payload: Vec<u8>, from: u32, to: u32) -> Result<Vec<u8>, IoError> {
+        for version in from..to {
+            let migration = match self.steps.get(&version) {
+                Some(migration) => migration,
+                None => return Err(IoError::MissingMigration { from: version }),
+            };
+            payload = migration(payload)?;
+        }
+        return Ok(payload);
+    }
+}
+
+//reads A payload with the layout of an older version and writes it with the new one, meant for
+//writing migrations without depending on the serialization
+pub fn convert_payload<Old, New>(
+    payload: &[u8],
+    convert: impl FnOnce(Old) -> New,
+) -> Result<Vec<u8>, IoError>
+where
+    Old: for<'de> Deserialize<'de>,
+    New: Serialize,
+{
+    let old: Old = bincode::deserialize(payload).map_err(IoError::Deserialize)?;
+    return bincode::serialize(&convert(old)).map_err(IoError::Serialize);
+}
+
+pub fn encode<T: SaveFormat>(obj: &T) -> Result<Vec<u8>, IoError> {
+    let mut body = T::FORMAT_VERSION.to_le_bytes().to_vec();
+    bincode::serialize_into(&mut body, obj).map_err(IoError::Serialize)?;
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&add_checksum(&body));
+    return Ok(bytes);
+}
+
+//payloads of older versions are migrated, newer versions can't be read
+pub fn decode<T: SaveFormat>(bytes: &[u8]) -> Result<T, IoError> {
+    if bytes.len() < MAGIC.len() {
+        return Err(IoError::Truncated);
+    }
+    if bytes[..MAGIC.len()] != MAGIC {
+        return Err(IoError::BadMagic);
+    }
+    let body = check_checksum(&bytes[MAGIC.len()..])?;
+    if body.len() < VERSION_BYTES {
+        return Err(IoError::Truncated);
+    }
+    let mut version = [0u8; VERSION_BYTES];
+    version.copy_from_slice(&body[..VERSION_BYTES]);
+    let version = u32::from_le_bytes(version);
+    let payload = &body[VERSION_BYTES..];
+    if version > T::FORMAT_VERSION {
+        return Err(IoError::UnsupportedVersion {
+            found: version,
+            supported: T::FORMAT_VERSION,
+        });
+    }
+    if version < T::FORMAT_VERSION {
+        let migrated = T::get_migrations().migrate(payload.to_vec(), version, T::FORMAT_VERSION)?;
+        return bincode::deserialize(&migrated).map_err(IoError::Deserialize);
+    }
+    return bincode::deserialize(payload).map_err(IoError::Deserialize);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::checksum::add_checksum;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Current(u32, u64);
+
+    impl SaveFormat for Current {
+        const FORMAT_VERSION: u32 = 2;
+        fn get_migrations() -> MigrationRegistry {
+            return MigrationRegistry::new().register(1, |payload| {
+                convert_payload(&payload, |old: Old| Current(old.0 as u32, old.1))
+            });
+        }
+    }
+
+    //the layout of Current in version 1
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Old(u16, u64);
+
+    impl SaveFormat for Old {
+        const FORMAT_VERSION: u32 = 1;
+    }
+
+    #[test]
+    fn older_versions_are_migrated() {
+        let bytes = encode(&Old(3, 4)).unwrap();
+        assert_eq!(decode::<Current>(&bytes).unwrap(), Current(3, 4));
+        assert_eq!(
+            decode::<Current>(&encode(&Current(5, 6)).unwrap()).unwrap(),
+            Current(5, 6)
+        );
+    }
+
+    #[test]
+    fn newer_versions_are_refused_without_counting_as_corrupt() {
+        let bytes = encode(&Current(3, 4)).unwrap();
+        let error = decode::<Old>(&bytes).unwrap_err();
+        assert!(matches!(
+            error,
+            IoError::UnsupportedVersion {
+                found: 2,
+                supported: 1
+            }
+        ));
+        assert!(!error.is_corrupt());
+    }
+
+    #[test]
+    fn payloads_without_a_header_are_corrupt() {
+        let payload = bincode::serialize(&Current(3, 4)).unwrap();
+        for bytes in [&payload[..], &add_checksum(&payload)[..], &[]].iter() {
+            assert!(decode::<Current>(bytes).unwrap_err().is_corrupt());
+        }
+    }
+
+    #[test]
+    fn damaged_payloads_are_corrupt() {
+        let bytes = encode(&Current(3, 4)).unwrap();
+        for i in MAGIC.len()..bytes.len() {
+            let mut damaged = bytes.clone();
+            damaged[i] ^= 0x10;
+            assert!(decode::<Current>(&damaged).unwrap_err().is_corrupt());
+        }
+        assert!(decode::<Current>(&bytes[..bytes.len() - 1])
+            .unwrap_err()
+            .is_corrupt());
+    }
+}
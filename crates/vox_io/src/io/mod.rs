@@ -1,2 +1,7 @@
+pub mod checksum;
+pub mod error;
 pub mod file_reader;
 pub mod file_writer;
+pub mod format;
+pub mod quarantine;
+pub mod region;
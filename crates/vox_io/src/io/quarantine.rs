@@ -0,0 +1,24 @@
+use crate::io::error::IoError;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//damaged files and region entries are moved into A quarantine directory instead of being deleted,
+//so they can still be looked at. Every call gets A new name, nothing in the directory is replaced
+pub fn get_quarantine_path(dir: &Path, name: &str) -> Result<PathBuf, IoError> {
+    std::fs::create_dir_all(dir)?;
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    return Ok(dir.join(format!("{}.{}", name, time)));
+}
+
+pub fn quarantine_file(path: &Path, dir: &Path) -> Result<PathBuf, IoError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let target = get_quarantine_path(dir, &name)?;
+    std::fs::rename(path, &target)?;
+    return Ok(target);
+}
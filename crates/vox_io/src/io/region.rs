@@ -0,0 +1,182 @@
+use crate::io::error::IoError;
+use crate::io::format::{decode, encode, SaveFormat};
+use crate::io::quarantine::get_quarantine_path;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+//A region file holds REGION_SIZE * REGION_SIZE entries. It starts with A table of (offset, length)
+//pairs, one per entry, as little endian u64s, an offset of 0 means the entry was never written.
+//Every entry is A header followed by the payload, see format.
+//Entries are always appended to the end of the file and only then pointed to by the table, so A
+//crash during A write leaves the previous version of the entry intact. Once less than half of the
+//file is in use, it is compacted into A new file which replaces the old one
+pub const REGION_SIZE: i32 = 16;
+const ENTRY_COUNT: usize = (REGION_SIZE * REGION_SIZE) as usize;
+const TABLE_ENTRY_BYTES: u64 = 16;
+const TABLE_BYTES: u64 = ENTRY_COUNT as u64 * TABLE_ENTRY_BYTES;
+
+//reads and writes of region files happen from the main thread and the chunk generation thread
+static REGION_LOCK: Mutex<()> = Mutex::new(());
+
+//the region containing the entry and the index of the entry within it
+pub fn get_region_location(x: i32, z: i32) -> ((i32, i32), usize) {
+    let region = (x.div_euclid(REGION_SIZE), z.div_euclid(REGION_SIZE));
+    let index = x.rem_euclid(REGION_SIZE) + z.rem_euclid(REGION_SIZE) * REGION_SIZE;
+    return (region, index as usize);
+}
+
+pub fn get_region_path(dir: &Path, region: (i32, i32)) -> PathBuf {
+    return dir.join(format!("r.{}.{}.region", region.0, region.1));
+}
+
+//None if the entry was never written
+pub fn read_from_region<T: SaveFormat>(dir: &Path, x: i32, z: i32) -> Result<Option<T>, IoError> {
+    let (region, index) = get_region_location(x, z);
+    let _lock = lock_regions();
+    let mut file = match File::open(get_region_path(dir, region)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let (offset, length) = read_table(&mut file)?[index];
+    if offset == 0 {
+        return Ok(None);
+    }
+    let bytes = read_entry(&mut file, offset, length)?;
+    return Ok(Some(decode(&bytes)?));
+}
+
+pub fn write_to_region<T: SaveFormat>(dir: &Path, x: i32, z: i32, obj: &T) -> Result<(), IoError> {
+    let (region, index) = get_region_location(x, z);
+    let bytes = encode(obj)?;
+    let _lock = lock_regions();
+    let path = get_region_path(dir, region);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    let offset = file.seek(SeekFrom::End(0))?.max(TABLE_BYTES);
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(&bytes)?;
+    file.sync_data()?;
+    write_table_entry(&mut file, index, (offset, bytes.len() as u64))?;
+    file.sync_data()?;
+
+    let table = read_table(&mut file)?;
+    let used: u64 = table.iter().map(|(_, length)| length).sum();
+    if offset + bytes.len() as u64 > TABLE_BYTES + used * 2 {
+        drop(file);
+        compact(&path, &table)?;
+    }
+    return Ok(());
+}
+
+//copies the raw bytes of an entry into the quarantine directory and removes it from the region,
+//so the next read sees an entry that was never written
+pub fn quarantine_region_entry(
+    dir: &Path,
+    x: i32,
+    z: i32,
+    quarantine_dir: &Path,
+) -> Result<PathBuf, IoError> {
+    let (region, index) = get_region_location(x, z);
+    let _lock = lock_regions();
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(get_region_path(dir, region))?;
+    let (offset, length) = read_table(&mut file)?[index];
+    //whatever is left of A truncated entry is kept
+    let file_length = file.metadata()?.len();
+    let available = file_length.saturating_sub(offset).min(length);
+    let bytes = read_entry(&mut file, offset, available)?;
+    let target = get_quarantine_path(quarantine_dir, &format!("{}.{}", x, z))?;
+    std::fs::write(&target, &bytes)?;
+    write_table_entry(&mut file, index, (0, 0))?;
+    file.sync_data()?;
+    return Ok(target);
+}
+
+//A thread that panicked while holding the lock can't have left A file half updated, the table
+//only changes after an entry is completely written
+fn lock_regions() -> MutexGuard<'static, ()> {
+    return REGION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+}
+
+//A new or empty file has an empty table
+fn read_table(file: &mut File) -> Result<Vec<(u64, u64)>, IoError> {
+    let mut bytes = vec![0u8; TABLE_BYTES as usize];
+    file.seek(SeekFrom::Start(0))?;
+    let mut read = 0;
+    while read < bytes.len() {
+        let n = file.read(&mut bytes[read..])?;
+        if n == 0 {
+            break;
+        }
+        read += n;
+    }
+    return Ok(bytes
+        .chunks_exact(TABLE_ENTRY_BYTES as usize)
+        .map(|entry| {
+            let mut offset = [0u8; 8];
+            let mut length = [0u8; 8];
+            offset.copy_from_slice(&entry[..8]);
+            length.copy_from_slice(&entry[8..]);
+            (u64::from_le_bytes(offset), u64::from_le_bytes(length))
+        })
+        .collect());
+}
+
+fn write_table_entry(file: &mut File, index: usize, entry: (u64, u64)) -> Result<(), IoError> {
+    let mut bytes = [0u8; TABLE_ENTRY_BYTES as usize];
+    bytes[..8].copy_from_slice(&entry.0.to_le_bytes());
+    bytes[8..].copy_from_slice(&entry.1.to_le_bytes());
+    file.seek(SeekFrom::Start(index as u64 * TABLE_ENTRY_BYTES))?;
+    file.write_all(&bytes)?;
+    return Ok(());
+}
+
+fn read_entry(file: &mut File, offset: u64, length: u64) -> Result<Vec<u8>, IoError> {
+    if offset < TABLE_BYTES || offset.saturating_add(length) > file.metadata()?.len() {
+        return Err(IoError::Truncated);
+    }
+    let mut bytes = vec![0u8; length as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut bytes)?;
+    return Ok(bytes);
+}
+
+//writes only the entries in use to A temporary file which then replaces the region file. Entries
+//that can't be read are left out, they would fail their checksum anyway
+fn compact(path: &Path, table: &[(u64, u64)]) -> Result<(), IoError> {
+    let temp_path = path.with_extension("region.tmp");
+    let result = (|| -> Result<(), IoError> {
+        let mut old = File::open(path)?;
+        let mut new = File::create(&temp_path)?;
+        let mut offset = TABLE_BYTES;
+        for (index, &(old_offset, length)) in table.iter().enumerate() {
+            if old_offset == 0 {
+                continue;
+            }
+            let bytes = match read_entry(&mut old, old_offset, length) {
+                Ok(bytes) => bytes,
+                Err(_) => continue,
+            };
+            new.seek(SeekFrom::Start(offset))?;
+            new.write_all(&bytes)?;
+            write_table_entry(&mut new, index, (offset, length))?;
+            offset += length;
+        }
+        new.sync_all()?;
+        std::fs::rename(&temp_path, path)?;
+        return Ok(());
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    return result;
+}
@@ -19,7 +19,7 @@ impl Renderer {
         let wgpu = WgpuState::new(&window);
         pipelines.insert(
             "main".to_string(),
-            WgpuPipeline::new(&wgpu.device, &wgpu.sc_desc),
+            WgpuPipeline::new(&wgpu.device, &wgpu.queue, &wgpu.sc_desc),
         );
         Renderer { pipelines, wgpu }
     }
@@ -0,0 +1,172 @@
+use image::imageops::FilterType;
+use image::RgbaImage;
+use std::num::NonZeroU32;
+use wgpu::{BindGroup, BindGroupLayout, Device, Queue, Sampler, TextureView};
+
+//textures which can't be loaded are replaced by A pink and black checkerboard
+const MISSING_TEXTURE_COLORS: [[u8; 4]; 2] = [[255, 0, 255, 255], [0, 0, 0, 255]];
+const DEFAULT_TEXTURE_SIZE: u32 = 16;
+
+pub struct TextureArray {
+    pub texture: wgpu::Texture,
+    pub view: TextureView,
+    pub sampler: Sampler,
+    pub bind_group: BindGroup,
+    pub layers: u32,
+}
+
+impl TextureArray {
+    pub fn get_bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: false,
+                        filtering: true,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("texture_array_bind_group_layout"),
+        })
+    }
+
+    //every file becomes one layer, in the same order as the given paths
+    pub fn from_files(
+        device: &Device,
+        queue: &Queue,
+        layout: &BindGroupLayout,
+        paths: &[String],
+    ) -> TextureArray {
+        let mut images: Vec<Option<RgbaImage>> = paths
+            .iter()
+            .map(|path| match image::open(path) {
+                Ok(img) => Some(img.to_rgba8()),
+                Err(e) => {
+                    println!("couldn't load texture {}: {}", path, e);
+                    None
+                }
+            })
+            .collect();
+        //an array texture needs at least one layer to be valid
+        if images.is_empty() {
+            images.push(None);
+        }
+        let (width, height) = images
+            .iter()
+            .flatten()
+            .next()
+            .map(|img| img.dimensions())
+            .unwrap_or((DEFAULT_TEXTURE_SIZE, DEFAULT_TEXTURE_SIZE));
+        let images: Vec<RgbaImage> = images
+            .into_iter()
+            .map(|img| match img {
+                Some(img) if img.dimensions() == (width, height) => img,
+                Some(img) => image::imageops::resize(&img, width, height, FilterType::Nearest),
+                None => missing_texture(width, height),
+            })
+            .collect();
+        TextureArray::new(device, queue, layout, &images, width, height)
+    }
+
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        layout: &BindGroupLayout,
+        images: &[RgbaImage],
+        width: u32,
+        height: u32,
+    ) -> TextureArray {
+        let layers = images.len() as u32;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("texture_array"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: layers,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+        for (layer, img) in images.iter().enumerate() {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                },
+                img.as_raw(),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(4 * width),
+                    rows_per_image: NonZeroU32::new(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("texture_array_bind_group"),
+        });
+        TextureArray {
+            texture,
+            view,
+            sampler,
+            bind_group,
+            layers,
+        }
+    }
+}
+
+fn missing_texture(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let checker = ((x * 2 / width) + (y * 2 / height)) % 2;
+        image::Rgba(MISSING_TEXTURE_COLORS[checker as usize])
+    })
+}
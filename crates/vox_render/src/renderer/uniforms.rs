@@ -1,18 +1,22 @@
 use crate::renderer::wgpu::gen_perspective_mat;
 use nalgebra::Vector3;
-use vox_core::constants::COLORS;
+use vox_core::constants::BLOCK_COLOR_COUNT;
 use vox_core::utils::get_rotation_matrix_y;
+use vox_core::world_config::get_chunk_size;
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Uniforms {
     view: [[f32; 4]; 4],
     perspective: [[f32; 4]; 4],
+    //position of the camera within its chunk
     viewer_pos: [f32; 3],
     time: f32,
     sun_dir: [f32; 3],
-    _padding: f32,
-    colors: [[f32; 4]; 16],
+    chunk_size: i32,
+    camera_chunk: [i32; 3],
+    _padding: i32,
+    colors: [[f32; 4]; BLOCK_COLOR_COUNT],
 }
 
 impl Uniforms {
@@ -33,13 +37,17 @@ impl Uniforms {
             viewer_pos: [0.0, 0.0, 0.0],
             sun_dir: [0.0, 0.0, 0.0],
             time: 0.0,
-            _padding: 0.0,
-            colors: COLORS,
+            chunk_size: get_chunk_size() as i32,
+            camera_chunk: [0, 0, 0],
+            _padding: 0,
+            colors: [[0.0; 4]; BLOCK_COLOR_COUNT],
         }
     }
 
+    //the view matrix only rotates, everything is drawn relative to the camera position
     pub fn update_view_proj(
         &mut self,
+        camera_chunk: [i32; 3],
         viewer_pos: [f32; 3],
         view_matrix: [[f32; 4]; 4],
         size: (u32, u32),
@@ -48,9 +56,13 @@ impl Uniforms {
         let (width, height) = size;
         self.view = view_matrix;
         self.perspective = gen_perspective_mat((width, height));
+        self.camera_chunk = camera_chunk;
         self.viewer_pos = viewer_pos;
         let sun_dir = get_rotation_matrix_y(time as f32) * Vector3::new(1.0, 1.0, 0.0);
         self.sun_dir = [sun_dir[0], sun_dir[1], sun_dir[2]];
         self.time = time as f32;
     }
+    pub fn set_colors(&mut self, colors: [[f32; 4]; BLOCK_COLOR_COUNT]) {
+        self.colors = colors;
+    }
 }
@@ -1,3 +1,7 @@
+//texture layer of vertices which use their color instead of A texture
+pub const NO_TEXTURE: u32 = u32::MAX;
+
+//_pos is relative to the origin of the chunk the vertex belongs to
 #[repr(C)]
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
@@ -5,22 +9,31 @@ pub struct Vertex {
     pub _color: u32,
     pub _normal: [f32; 3],
     pub _type: u32,
+    pub _uv: [f32; 2],
+    pub _layer: u32,
 }
 
 pub fn vertex(pos: [f32; 3], col: u32, nor: [f32; 3]) -> Vertex {
-    Vertex {
-        _pos: [pos[0], pos[1], pos[2]],
-        _color: col,
-        _normal: [nor[0], nor[1], nor[2]],
-        _type: 0,
-    }
+    vertex_typed(pos, col, nor, 0)
 }
 pub fn vertex_typed(pos: [f32; 3], col: u32, nor: [f32; 3], block_type: u32) -> Vertex {
+    vertex_textured(pos, col, nor, block_type, [0f32, 0f32], NO_TEXTURE)
+}
+pub fn vertex_textured(
+    pos: [f32; 3],
+    col: u32,
+    nor: [f32; 3],
+    block_type: u32,
+    uv: [f32; 2],
+    layer: u32,
+) -> Vertex {
     Vertex {
         _pos: [pos[0], pos[1], pos[2]],
         _color: col,
         _normal: [nor[0], nor[1], nor[2]],
         _type: block_type,
+        _uv: uv,
+        _layer: layer,
     }
 }
 
@@ -55,7 +68,48 @@ impl Vertex {
                     shader_location: 3,
                     format: wgpu::VertexFormat::Uint32,
                 },
+                wgpu::VertexAttribute {
+                    offset: (std::mem::size_of::<[f32; 3]>()
+                        + std::mem::size_of::<u32>()
+                        + std::mem::size_of::<[f32; 3]>()
+                        + std::mem::size_of::<u32>())
+                        as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: (std::mem::size_of::<[f32; 3]>()
+                        + std::mem::size_of::<u32>()
+                        + std::mem::size_of::<[f32; 3]>()
+                        + std::mem::size_of::<u32>()
+                        + std::mem::size_of::<[f32; 2]>())
+                        as wgpu::BufferAddress,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Uint32,
+                },
             ],
         }
     }
 }
+
+//position of the chunk A mesh belongs to, given once per draw as an instance attribute. The
+//shader subtracts the chunk of the camera in integers, so the mesh is drawn relative to the camera
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ChunkOrigin {
+    pub _chunk: [i32; 3],
+}
+
+impl ChunkOrigin {
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ChunkOrigin>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 6,
+                format: wgpu::VertexFormat::Sint32x3,
+            }],
+        }
+    }
+}
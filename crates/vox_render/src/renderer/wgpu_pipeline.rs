@@ -1,6 +1,7 @@
 use crate::renderer::depth_texture::DepthTexture;
+use crate::renderer::texture_array::TextureArray;
 use crate::renderer::uniforms::Uniforms;
-use crate::renderer::vertex::Vertex;
+use crate::renderer::vertex::{ChunkOrigin, Vertex};
 use wgpu::util::DeviceExt;
 use wgpu::{
     BlendFactor, BlendOperation, BufferBinding, Device, Queue, RenderPass, SwapChainDescriptor,
@@ -12,11 +13,15 @@ pub struct WgpuPipeline {
     pub uniforms: Uniforms,
     pub uniform_bind_group: wgpu::BindGroup,
     pub render_pipeline: wgpu::RenderPipeline,
+    pub texture_bind_group_layout: wgpu::BindGroupLayout,
+    pub textures: TextureArray,
 }
 
 impl WgpuPipeline {
-    pub fn new(device: &Device, sc_desc: &SwapChainDescriptor) -> WgpuPipeline {
+    pub fn new(device: &Device, queue: &Queue, sc_desc: &SwapChainDescriptor) -> WgpuPipeline {
         let uniforms = Uniforms::new();
+        let texture_bind_group_layout = TextureArray::get_bind_group_layout(device);
+        let textures = TextureArray::from_files(device, queue, &texture_bind_group_layout, &[]);
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
@@ -60,7 +65,7 @@ impl WgpuPipeline {
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&uniform_bind_group_layout],
+                bind_group_layouts: &[&uniform_bind_group_layout, &texture_bind_group_layout],
                 push_constant_ranges: &[],
             });
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -69,7 +74,7 @@ impl WgpuPipeline {
             vertex: wgpu::VertexState {
                 module: &vs_module,
                 entry_point: "main", // 1.
-                buffers: &[Vertex::desc()],
+                buffers: &[Vertex::desc(), ChunkOrigin::desc()],
             },
             primitive: wgpu::PrimitiveState {
                 topology: wgpu::PrimitiveTopology::TriangleList,
@@ -121,11 +126,18 @@ impl WgpuPipeline {
             uniform_bind_group,
             uniform_buffer,
             render_pipeline,
+            texture_bind_group_layout,
+            textures,
         };
     }
+    pub fn set_textures(&mut self, device: &Device, queue: &Queue, paths: &[String]) {
+        self.textures =
+            TextureArray::from_files(device, queue, &self.texture_bind_group_layout, paths);
+    }
     pub fn setup_render_pass<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.textures.bind_group, &[]);
     }
     pub fn set_uniform_buffer(&self, queue: &Queue, uniforms: Uniforms) {
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
@@ -2,9 +2,19 @@
 #version 450
 
 layout(location=0) in vec4 v_color;
+layout(location=1) in vec2 v_uv;
+layout(location=2) flat in uint v_layer;
 layout(location=0) out vec4 f_color;
 
-void main() {
+layout(set=1, binding=0) uniform texture2DArray t_blocks;
+layout(set=1, binding=1) uniform sampler s_blocks;
+
+const uint NO_TEXTURE = 0xFFFFFFFFu;
 
-    f_color = v_color;
+void main() {
+    if (v_layer == NO_TEXTURE){
+        f_color = v_color;
+    } else {
+        f_color = texture(sampler2DArray(t_blocks, s_blocks), vec3(v_uv, float(v_layer))) * v_color;
+    }
 }
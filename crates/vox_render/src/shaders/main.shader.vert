@@ -5,8 +5,15 @@ layout(location=0) in vec3 a_position;
 layout(location=1) in uint a_color;
 layout(location=2) in vec3 a_normal;
 layout(location=3) in uint type;
+layout(location=4) in vec2 a_uv;
+layout(location=5) in uint a_layer;
+layout(location=6) in ivec3 a_chunk;
 
 layout(location=0) out vec4 v_color;
+layout(location=1) out vec2 v_uv;
+layout(location=2) flat out uint v_layer;
+
+const uint NO_TEXTURE = 0xFFFFFFFFu;
 
 layout(set=0, binding=0)
 uniform Uniforms {
@@ -15,23 +22,36 @@ uniform Uniforms {
     vec3 viewer_pos;
     float time;
     vec3 sun_dir;
-    float _padding;
-    vec4 colors[16];
+    int chunk_size;
+    ivec3 camera_chunk;
+    int _padding;
+    vec4 colors[256];
 };
 
 const vec3 diffuse_color = vec3(1.0, 1.0, 1.0);
 
 void main() {
-    vec3 perm_position = a_position;
-    if (type == 6){
-        float perm_x = cos(perm_position[0]+time)/4;
-        float perm_y = cos(perm_position[1]+time)/4;
-        float perm_z = cos(perm_position[2]+time)/4;
+    //the chunk difference is taken in integers, so only positions close to the camera become floats
+    vec3 chunk_offset = vec3((a_chunk - camera_chunk) * chunk_size);
+    vec3 perm_position = chunk_offset + a_position - viewer_pos;
+    //type is 1 for animated blocks
+    if (type == 1){
+        //the phase follows the world position so neighboring chunks move together
+        vec3 phase = a_position + vec3((a_chunk * chunk_size) & 1023);
+        float perm_x = cos(phase[0]+time)/4;
+        float perm_y = cos(phase[1]+time)/4;
+        float perm_z = cos(phase[2]+time)/4;
         vec3 permutation = vec3(perm_x, perm_y, perm_z);
         perm_position = perm_position + permutation;
     }
     float diffuse = max(dot(normalize(a_normal), normalize(sun_dir)), 0.1);
     vec4 new_color = vec4(colors[a_color][0]/255,colors[a_color][1]/255,colors[a_color][2]/255,colors[a_color][3]/255);
+    //textured vertices get their color from the texture in the fragment shader
+    if (a_layer != NO_TEXTURE){
+        new_color = vec4(1.0, 1.0, 1.0, 1.0);
+    }
+    v_uv = a_uv;
+    v_layer = a_layer;
     v_color = new_color * vec4(diffuse_color * diffuse,1);
     gl_Position = u_perspective * u_view * vec4(perm_position, 1.0);
 }
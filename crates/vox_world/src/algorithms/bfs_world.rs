@@ -1,6 +1,6 @@
-use crate::blocks::block::{get_blocktype, BlockId};
-use crate::blocks::block_type::BlockType;
-use crate::world_gen::meta_chunk::MetaChunk;
+use crate::blocks::block::BlockId;
+use crate::blocks::block_registry::get_block_registry;
+use crate::world::voxel_world::VoxelWorld;
 use std::collections::{HashSet, VecDeque};
 use vox_core::positions::GlobalBlockPos;
 
@@ -41,10 +41,15 @@ impl Blocksides {
     }
 }
 
-pub fn bfs_world_air(pos: &GlobalBlockPos, depth: u32, world: &mut MetaChunk, block: BlockId) {
+pub fn bfs_world_air(
+    pos: &GlobalBlockPos,
+    depth: u32,
+    world: &mut impl VoxelWorld,
+    block: BlockId,
+) {
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
-    let sides = get_surrounding_blocks(world, pos, |b: BlockId| get_blocktype(b) == BlockType::Air);
+    let sides = get_surrounding_blocks(world, pos, is_open);
     push_sides(&mut queue, &visited, &sides, pos, 0);
     visited.insert(*pos);
     while let Some((temp_pos, d)) = queue.pop_front() {
@@ -52,14 +57,17 @@ pub fn bfs_world_air(pos: &GlobalBlockPos, depth: u32, world: &mut MetaChunk, bl
             world.set_block(&temp_pos, block);
             continue;
         }
-        let sides = get_surrounding_blocks(world, &temp_pos, |b: BlockId| {
-            get_blocktype(b) == BlockType::Air
-        });
+        let sides = get_surrounding_blocks(world, &temp_pos, is_open);
         push_sides(&mut queue, &visited, &sides, &temp_pos, d + 1);
         visited.insert(temp_pos);
     }
 }
 
+fn is_open(block: BlockId) -> bool {
+    let properties = get_block_registry().get(block).properties;
+    properties.replaceable && !properties.fluid
+}
+
 fn push_sides(
     queue: &mut VecDeque<(GlobalBlockPos, u32)>,
     visited: &HashSet<GlobalBlockPos>,
@@ -154,30 +162,30 @@ fn push_sides(
 }
 
 fn get_surrounding_blocks(
-    world: &MetaChunk,
+    world: &impl VoxelWorld,
     pos: &GlobalBlockPos,
     f: impl Fn(BlockId) -> bool,
 ) -> Blocksides {
     let mut sides = Blocksides::new();
-    update_side(&world, &pos.get_diff(1, 0, 0), &f, &mut sides.right);
-    update_side(&world, &pos.get_diff(-1, 0, 0), &f, &mut sides.left);
-    update_side(&world, &pos.get_diff(0, 1, 0), &f, &mut sides.top);
-    update_side(&world, &pos.get_diff(-1, 1, 0), &f, &mut sides.top_left);
-    update_side(&world, &pos.get_diff(1, 1, 0), &f, &mut sides.top_right);
-    update_side(&world, &pos.get_diff(0, 1, 1), &f, &mut sides.top_front);
-    update_side(&world, &pos.get_diff(0, 1, -1), &f, &mut sides.top_back);
-    update_side(&world, &pos.get_diff(0, -1, 0), &f, &mut sides.bottom);
-    update_side(&world, &pos.get_diff(-1, -1, 0), &f, &mut sides.bottom_left);
-    update_side(&world, &pos.get_diff(1, -1, 0), &f, &mut sides.bottom_right);
-    update_side(&world, &pos.get_diff(0, -1, 1), &f, &mut sides.bottom_front);
-    update_side(&world, &pos.get_diff(0, -1, -1), &f, &mut sides.bottom_back);
-    update_side(&world, &pos.get_diff(0, 0, 1), &f, &mut sides.front);
-    update_side(&world, &pos.get_diff(0, 0, -1), f, &mut sides.back);
+    update_side(world, &pos.get_diff(1, 0, 0), &f, &mut sides.right);
+    update_side(world, &pos.get_diff(-1, 0, 0), &f, &mut sides.left);
+    update_side(world, &pos.get_diff(0, 1, 0), &f, &mut sides.top);
+    update_side(world, &pos.get_diff(-1, 1, 0), &f, &mut sides.top_left);
+    update_side(world, &pos.get_diff(1, 1, 0), &f, &mut sides.top_right);
+    update_side(world, &pos.get_diff(0, 1, 1), &f, &mut sides.top_front);
+    update_side(world, &pos.get_diff(0, 1, -1), &f, &mut sides.top_back);
+    update_side(world, &pos.get_diff(0, -1, 0), &f, &mut sides.bottom);
+    update_side(world, &pos.get_diff(-1, -1, 0), &f, &mut sides.bottom_left);
+    update_side(world, &pos.get_diff(1, -1, 0), &f, &mut sides.bottom_right);
+    update_side(world, &pos.get_diff(0, -1, 1), &f, &mut sides.bottom_front);
+    update_side(world, &pos.get_diff(0, -1, -1), &f, &mut sides.bottom_back);
+    update_side(world, &pos.get_diff(0, 0, 1), &f, &mut sides.front);
+    update_side(world, &pos.get_diff(0, 0, -1), f, &mut sides.back);
     return sides;
 }
 
 fn update_side(
-    world: &MetaChunk,
+    world: &impl VoxelWorld,
     pos: &GlobalBlockPos,
     f: impl Fn(BlockId) -> bool,
     side: &mut bool,
@@ -1 +1,2 @@
 pub mod bfs_world;
+pub mod raycast;
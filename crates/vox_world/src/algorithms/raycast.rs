@@ -0,0 +1,131 @@
+use crate::blocks::block::AIR;
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
+use crate::world::voxel_world::VoxelWorld;
+use vox_core::positions::{GlobalBlockPos, ObjectPos, ToBlockPos};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    pub block: GlobalBlockPos,
+    pub state: BlockState,
+    //points out of the face the ray entered through, all zero when the ray started inside the block
+    pub normal: [i32; 3],
+    //distance along the ray to the point where it entered the block
+    pub distance: f64,
+    //the last cell the ray passed through before the hit, this is where A block placed against
+    //the hit face ends up
+    pub previous: GlobalBlockPos,
+}
+
+//walks the grid cell by cell (Amanatides & Woo) until is_hit accepts A block or max_distance is
+//reached. Unloaded blocks are passed through like air
+pub fn raycast(
+    world: &impl VoxelWorld,
+    origin: &ObjectPos,
+    direction: [f64; 3],
+    max_distance: f64,
+    is_hit: impl Fn(BlockState) -> bool,
+) -> Option<RaycastHit> {
+    let length =
+        (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2])
+            .sqrt();
+    if length == 0.0 || !length.is_finite() {
+        return None;
+    }
+    let dir = [
+        direction[0] / length,
+        direction[1] / length,
+        direction[2] / length,
+    ];
+    let start = [origin.x, origin.y, origin.z];
+    let mut cell = origin.get_global_block_pos();
+    let mut cell_coords = [cell.x, cell.y, cell.z];
+
+    let mut step = [0i32; 3];
+    //distance along the ray to the next cell boundary on each axis
+    let mut t_max = [f64::INFINITY; 3];
+    //distance along the ray between two boundaries on each axis
+    let mut t_delta = [f64::INFINITY; 3];
+    for axis in 0..3 {
+        if dir[axis] > 0.0 {
+            step[axis] = 1;
+            t_max[axis] = ((cell_coords[axis] + 1) as f64 - start[axis]) / dir[axis];
+            t_delta[axis] = 1.0 / dir[axis];
+        } else if dir[axis] < 0.0 {
+            step[axis] = -1;
+            t_max[axis] = (cell_coords[axis] as f64 - start[axis]) / dir[axis];
+            t_delta[axis] = -1.0 / dir[axis];
+        }
+    }
+
+    let mut previous = cell;
+    let mut normal = [0i32; 3];
+    let mut distance = 0.0;
+    loop {
+        if let Some(state) = world.get_block_state(&cell) {
+            if is_hit(state) {
+                return Some(RaycastHit {
+                    block: cell,
+                    state,
+                    normal,
+                    distance,
+                    previous,
+                });
+            }
+        }
+        let axis = if t_max[0] < t_max[1] {
+            if t_max[0] < t_max[2] {
+                0
+            } else {
+                2
+            }
+        } else if t_max[1] < t_max[2] {
+            1
+        } else {
+            2
+        };
+        distance = t_max[axis];
+        if distance > max_distance {
+            return None;
+        }
+        previous = cell;
+        cell_coords[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        normal = [0, 0, 0];
+        normal[axis] = -step[axis];
+        cell = GlobalBlockPos {
+            x: cell_coords[0],
+            y: cell_coords[1],
+            z: cell_coords[2],
+        };
+    }
+}
+
+//the block the player is looking at, air and fluids can be looked through
+pub fn raycast_blocks(
+    world: &impl VoxelWorld,
+    origin: &ObjectPos,
+    direction: [f64; 3],
+    max_distance: f64,
+) -> Option<RaycastHit> {
+    let registry = get_block_registry();
+    return raycast(world, origin, direction, max_distance, |state| {
+        state.id != AIR && !registry.get(state.id).properties.fluid
+    });
+}
+
+//whether nothing that occludes is between the two positions
+pub fn has_line_of_sight(world: &impl VoxelWorld, from: &ObjectPos, to: &ObjectPos) -> bool {
+    let direction = [to.x - from.x, to.y - from.y, to.z - from.z];
+    let distance =
+        (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2])
+            .sqrt();
+    let registry = get_block_registry();
+    let target = to.get_global_block_pos();
+    return match raycast(world, from, direction, distance, |state| {
+        registry.get(state.id).properties.occludes
+    }) {
+        None => true,
+        Some(hit) => hit.block == target,
+    };
+}
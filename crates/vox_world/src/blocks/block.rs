@@ -1,57 +1,111 @@
-use crate::blocks::block_type::BlockType;
-use crate::blocks::blockside::BlockSides;
-use rand::distributions::{Distribution, Standard};
-use rand::Rng;
-use serde::{Deserialize, Serialize};
-use vox_core::constants::COLORS;
-use vox_core::positions::{GlobalBlockPos, ObjectPos};
-use vox_render::renderer::vertex::{vertex, vertex_typed, Vertex};
+use crate::blocks::block_model::FULL_FACE;
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
+use crate::blocks::blockside::{BlockSides, Side};
+use vox_core::positions::LocalBlockPos;
+use vox_render::renderer::vertex::Vertex;
 
 pub type BlockId = u8;
 
+//ids of the blocks the world generator relies on, see BlockRegistry::with_default_blocks
+pub const GRASS: BlockId = 0;
+pub const WATER: BlockId = 1;
+pub const DIRT: BlockId = 2;
+pub const STONE: BlockId = 3;
+pub const SAND: BlockId = 4;
+pub const AIR: BlockId = 5;
+pub const LEAF: BlockId = 6;
+pub const STONE_SLAB: BlockId = 7;
+pub const STONE_STAIRS: BlockId = 8;
+pub const TALL_GRASS: BlockId = 9;
+pub const GLASS_PANE: BlockId = 10;
+pub const FENCE: BlockId = 11;
+pub const UNKNOWN: BlockId = 255;
+
+//side is the side of the source block which touches the neighbor
 #[inline]
-pub fn get_blocktype(block_id: BlockId) -> BlockType {
-    match block_id {
-        0 => BlockType::Grass,
-        1 => BlockType::Water,
-        2 => BlockType::Dirt,
-        3 => BlockType::Stone,
-        4 => BlockType::Sand,
-        5 => BlockType::Air,
-        6 => BlockType::Leaf,
-        _ => BlockType::Unknown,
-    }
-}
-#[inline]
-pub fn get_blockid(block: BlockType) -> BlockId {
-    match block {
-        BlockType::Grass => 0,
-        BlockType::Water => 1,
-        BlockType::Dirt => 2,
-        BlockType::Stone => 3,
-        BlockType::Sand => 4,
-        BlockType::Air => 5,
-        BlockType::Leaf => 6,
-        BlockType::Unknown => 255,
-    }
-}
-#[inline]
-pub fn should_render_against(source_block_id: BlockId, neighbor_block_id: BlockId) -> bool {
-    if source_block_id == neighbor_block_id {
-        return false;
-    }
-    if source_block_id == get_blockid(BlockType::Leaf) {
+pub fn should_render_against(source: BlockState, neighbor: BlockState, side: Side) -> bool {
+    let registry = get_block_registry();
+    let source_definition = registry.get(source.id);
+    let neighbor_definition = registry.get(neighbor.id);
+    //blocks of the same kind hide each other where they touch, even if they can be seen through
+    let same_block = source.id == neighbor.id;
+    if !same_block && (source_definition.animated || !neighbor_definition.properties.occludes) {
         return true;
     }
-    if COLORS[neighbor_block_id as usize][3] == 255.0 {
-        return false;
+    //A side without faces still lets the inside of the block be seen through it
+    let mut faces = source_definition.model.get_faces(source, side);
+    if faces.is_empty() {
+        faces.push(FULL_FACE);
     }
-    return true;
+    return faces.iter().any(|face| {
+        !neighbor_definition
+            .model
+            .covers_face(neighbor, side.opposite(), face)
+    });
 }
 pub fn get_mesh(
-    block_id: BlockId,
-    pos: &GlobalBlockPos,
+    state: BlockState,
+    pos: &LocalBlockPos,
     sides: &BlockSides,
 ) -> (Vec<Vertex>, Vec<u32>) {
-    crate::blocks::block_mesh::get_mesh(block_id, pos, sides)
+    crate::blocks::block_mesh::get_mesh(state, pos, sides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::block_state::{Axis, Half};
+
+    #[test]
+    fn stacked_bottom_slabs_keep_the_face_over_the_gap() {
+        let slab = BlockState::new(STONE_SLAB);
+        //the upper slab floats above the gap at the top of the lower slab
+        assert!(should_render_against(slab, slab, Side::Bot));
+        //the gap is closed by the upper slab, so the top of the lower slab can't be seen
+        assert!(!should_render_against(slab, slab, Side::Top));
+        //side by side their side faces match exactly
+        assert!(!should_render_against(slab, slab, Side::Right));
+    }
+
+    #[test]
+    fn slabs_are_hidden_by_full_blocks_only_where_covered() {
+        let bottom = BlockState::new(STONE_SLAB);
+        let top = bottom.with_half(Half::Top);
+        let stone = BlockState::new(STONE);
+        assert!(!should_render_against(bottom, stone, Side::Right));
+        assert!(should_render_against(stone, bottom, Side::Right));
+        assert!(!should_render_against(stone, bottom, Side::Top));
+        assert!(should_render_against(stone, top, Side::Top));
+    }
+
+    #[test]
+    fn stairs_facing_different_axes_show_their_steps() {
+        let x = BlockState::new(STONE_STAIRS).with_axis(Axis::X);
+        let z = x.with_axis(Axis::Z);
+        assert!(!should_render_against(x, x, Side::Back));
+        assert!(should_render_against(x, z, Side::Back));
+    }
+
+    #[test]
+    fn fluids_only_hide_lower_levels() {
+        let full = BlockState::new(WATER);
+        let half = full.with_fluid_level(4);
+        assert!(!should_render_against(full, full, Side::Right));
+        assert!(should_render_against(full, half, Side::Right));
+        assert!(!should_render_against(half, full, Side::Right));
+    }
+
+    #[test]
+    fn fences_do_not_hide_their_neighbors() {
+        let fence = BlockState::new(FENCE);
+        let stone = BlockState::new(STONE);
+        assert!(should_render_against(stone, fence, Side::Right));
+        assert!(!should_render_against(fence, stone, Side::Right));
+        assert!(should_render_against(
+            fence,
+            fence.with_axis(Axis::Z),
+            Side::Right
+        ));
+    }
 }
@@ -1,236 +1,533 @@
-use crate::blocks::block::{get_blocktype, BlockId};
-use crate::blocks::block_type::BlockType;
-use crate::blocks::blockside::BlockSides;
-use vox_core::constants::COLORS;
-use vox_core::positions::{GlobalBlockPos, ObjectPos};
-use vox_render::renderer::vertex::{vertex_typed, Vertex};
+use crate::blocks::block::{BlockId, AIR};
+use crate::blocks::block_model::{BlockBox, BlockModel};
+use crate::blocks::block_registry::{get_block_registry, TextureFace};
+use crate::blocks::block_state::{Axis, BlockState};
+use crate::blocks::blockside::{BlockSides, Side};
+use std::f32::consts::FRAC_1_SQRT_2;
+use vox_core::positions::LocalBlockPos;
+use vox_render::renderer::vertex::{vertex_textured, Vertex};
 
 pub fn get_mesh(
-    block_id: BlockId,
-    pos: &GlobalBlockPos,
+    state: BlockState,
+    pos: &LocalBlockPos,
     sides: &BlockSides,
 ) -> (Vec<Vertex>, Vec<u32>) {
+    let block_id = state.id;
     let mut vertices: Vec<Vertex> = Vec::with_capacity(8);
     let mut indices: Vec<u32> = Vec::with_capacity(36);
-    if get_blocktype(block_id) == BlockType::Air {
+    if block_id == AIR {
         return (Vec::new(), Vec::new());
     }
-    let posf = pos.get_block_pos();
-    if sides.right {
-        mesh_right(block_id, &posf, &mut vertices, &mut indices);
-    }
-    if sides.left {
-        mesh_left(block_id, &posf, &mut vertices, &mut indices);
+    let definition = get_block_registry().get(block_id);
+    let block_type = definition.animated as u32;
+    let layers = FaceLayers::new(state);
+    //meshes are built relative to their chunk, the chunk origin is added at draw time
+    let posf = [pos.x as f32, pos.y as f32, pos.z as f32];
+    if definition.model == BlockModel::Cross {
+        let layer = layers.get(Side::Front);
+        mesh_cross(
+            block_id,
+            block_type,
+            layer,
+            &posf,
+            &mut vertices,
+            &mut indices,
+        );
+        return (vertices, indices);
     }
-    if sides.top {
-        mesh_top(block_id, &posf, &mut vertices, &mut indices);
+    for b in definition.model.get_boxes(state).iter() {
+        //faces inside of the block can't be hidden by A neighbor
+        let visible = |side: Side| !b.touches(side) || sides.get(side);
+        if visible(Side::Right) {
+            mesh_right(
+                block_id,
+                block_type,
+                layers.get(Side::Right),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
+        if visible(Side::Left) {
+            mesh_left(
+                block_id,
+                block_type,
+                layers.get(Side::Left),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
+        if visible(Side::Top) {
+            mesh_top(
+                block_id,
+                block_type,
+                layers.get(Side::Top),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
+        if visible(Side::Bot) {
+            mesh_bottom(
+                block_id,
+                block_type,
+                layers.get(Side::Bot),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
+        if visible(Side::Back) {
+            mesh_back(
+                block_id,
+                block_type,
+                layers.get(Side::Back),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
+        if visible(Side::Front) {
+            mesh_front(
+                block_id,
+                block_type,
+                layers.get(Side::Front),
+                &posf,
+                b,
+                &mut vertices,
+                &mut indices,
+            );
+        }
     }
-    if sides.bot {
-        mesh_bottom(block_id, &posf, &mut vertices, &mut indices);
+    return (vertices, indices);
+}
+//texture layers of the faces of A block, rotated to match the axis of the block state if the block
+//rotates its textures
+struct FaceLayers {
+    top: u32,
+    side: u32,
+    bottom: u32,
+    axis: Axis,
+}
+
+impl FaceLayers {
+    fn new(state: BlockState) -> FaceLayers {
+        let registry = get_block_registry();
+        let axis = if registry.get(state.id).rotates_textures {
+            state.get_axis()
+        } else {
+            Axis::Y
+        };
+        FaceLayers {
+            top: registry.get_texture_layer(state.id, TextureFace::Top),
+            side: registry.get_texture_layer(state.id, TextureFace::Side),
+            bottom: registry.get_texture_layer(state.id, TextureFace::Bottom),
+            axis,
+        }
     }
-    if sides.back {
-        mesh_back(block_id, &posf, &mut vertices, &mut indices);
+    fn get(&self, side: Side) -> u32 {
+        let (top, bottom) = match self.axis {
+            Axis::X => (Side::Right, Side::Left),
+            Axis::Y => (Side::Top, Side::Bot),
+            Axis::Z => (Side::Back, Side::Front),
+        };
+        if side == top {
+            self.top
+        } else if side == bottom {
+            self.bottom
+        } else {
+            self.side
+        }
     }
-    if sides.front {
-        mesh_front(block_id, &posf, &mut vertices, &mut indices);
+}
+
+//the uv coordinates are taken from the position of the vertex within its block
+#[inline]
+fn face_vertex(
+    vertex_pos: [f32; 3],
+    block_pos: &[f32; 3],
+    block_id: BlockId,
+    normal: [f32; 3],
+    block_type: u32,
+    layer: u32,
+) -> Vertex {
+    let x = vertex_pos[0] - block_pos[0];
+    let y = vertex_pos[1] - block_pos[1];
+    let z = vertex_pos[2] - block_pos[2];
+    let uv = if normal[0] != 0f32 {
+        [z, 1f32 - y]
+    } else if normal[1] != 0f32 {
+        [x, z]
+    } else {
+        [x, 1f32 - y]
+    };
+    vertex_textured(vertex_pos, block_id as u32, normal, block_type, uv, layer)
+}
+#[inline]
+fn get_corners(pos: &[f32; 3], b: &BlockBox) -> ([f32; 3], [f32; 3]) {
+    (
+        [pos[0] + b.min[0], pos[1] + b.min[1], pos[2] + b.min[2]],
+        [pos[0] + b.max[0], pos[1] + b.max[1], pos[2] + b.max[2]],
+    )
+}
+//two diagonal quads, both are added twice with opposite winding so they are visible from both sides
+fn mesh_cross(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let quads = [
+        (
+            [0f32, 0f32],
+            [1f32, 1f32],
+            [FRAC_1_SQRT_2, 0f32, -FRAC_1_SQRT_2],
+        ),
+        (
+            [1f32, 0f32],
+            [0f32, 1f32],
+            [FRAC_1_SQRT_2, 0f32, FRAC_1_SQRT_2],
+        ),
+    ];
+    for (start, end, normal) in quads.iter() {
+        for flip in [false, true].iter() {
+            let (i0, i1) = if *flip { (2, 1) } else { (1, 2) };
+            indices.push(vec.len() as u32);
+            indices.push((vec.len() + i0) as u32);
+            indices.push((vec.len() + i1) as u32);
+            indices.push((vec.len() + i0) as u32);
+            indices.push((vec.len() + 3) as u32);
+            indices.push((vec.len() + i1) as u32);
+            let normal = if *flip {
+                [-normal[0], -normal[1], -normal[2]]
+            } else {
+                *normal
+            };
+            for (x, y, z, u) in [
+                (start[0], 0f32, start[1], 0f32),
+                (end[0], 0f32, end[1], 1f32),
+                (start[0], 1f32, start[1], 0f32),
+                (end[0], 1f32, end[1], 1f32),
+            ]
+            .iter()
+            {
+                vec.push(vertex_textured(
+                    [pos[0] + x, pos[1] + y, pos[2] + z],
+                    block_id as u32,
+                    normal,
+                    block_type,
+                    [*u, 1f32 - y],
+                    layer,
+                ));
+            }
+        }
     }
-    return (vertices, indices);
 }
 #[inline]
-fn mesh_front(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_front(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], l[2]],
+        pos,
+        block_id,
         [0f32, 0f32, 1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], l[2]],
+        pos,
+        block_id,
         [0f32, 0f32, 1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], l[2]],
+        pos,
+        block_id,
         [0f32, 0f32, 1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], l[2]],
+        pos,
+        block_id,
         [0f32, 0f32, 1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
 #[inline]
-fn mesh_back(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_back(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], h[2]],
+        pos,
+        block_id,
         [0f32, 0f32, -1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], h[2]],
+        pos,
+        block_id,
         [0f32, 0f32, -1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], h[2]],
+        pos,
+        block_id,
         [0f32, 0f32, -1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], h[2]],
+        pos,
+        block_id,
         [0f32, 0f32, -1f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
 #[inline]
-fn mesh_left(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_left(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], l[2]],
+        pos,
+        block_id,
         [-1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], l[2]],
+        pos,
+        block_id,
         [-1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], h[2]],
+        pos,
+        block_id,
         [-1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], h[2]],
+        pos,
+        block_id,
         [-1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
 #[inline]
-fn mesh_right(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_right(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], l[2]],
+        pos,
+        block_id,
         [1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], h[2]],
+        pos,
+        block_id,
         [1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], l[2]],
+        pos,
+        block_id,
         [1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], h[2]],
+        pos,
+        block_id,
         [1f32, 0f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
 #[inline]
-fn mesh_top(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_top(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], l[2]],
+        pos,
+        block_id,
         [0f32, 1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], l[2]],
+        pos,
+        block_id,
         [0f32, 1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], h[1], h[2]],
+        pos,
+        block_id,
         [0f32, 1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y + 1f32, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], h[1], h[2]],
+        pos,
+        block_id,
         [0f32, 1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
 #[inline]
-fn mesh_bottom(block_id: BlockId, pos: &ObjectPos, vec: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+fn mesh_bottom(
+    block_id: BlockId,
+    block_type: u32,
+    layer: u32,
+    pos: &[f32; 3],
+    b: &BlockBox,
+    vec: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    let (l, h) = get_corners(pos, b);
     indices.push((vec.len() + 0) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 2) as u32);
     indices.push((vec.len() + 1) as u32);
     indices.push((vec.len() + 3) as u32);
     indices.push((vec.len() + 2) as u32);
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], l[2]],
+        pos,
+        block_id,
         [0f32, -1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [l[0], l[1], h[2]],
+        pos,
+        block_id,
         [0f32, -1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], l[2]],
+        pos,
+        block_id,
         [0f32, -1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
-    vec.push(vertex_typed(
-        [pos.x + 1f32, pos.y, pos.z + 1f32],
-        block_id as u32,
+    vec.push(face_vertex(
+        [h[0], l[1], h[2]],
+        pos,
+        block_id,
         [0f32, -1f32, 0f32],
-        block_id as u32,
+        block_type,
+        layer,
     ));
 }
@@ -0,0 +1,150 @@
+use crate::blocks::block_state::{Axis, BlockState, Half, MAX_FLUID_LEVEL};
+use crate::blocks::blockside::Side;
+
+//thickness of panes, in blocks
+const PANE_WIDTH: f32 = 2.0 / 16.0;
+//width of the post in the middle of A fence and thickness of its rails, in blocks
+const FENCE_POST_WIDTH: f32 = 4.0 / 16.0;
+const FENCE_RAIL_WIDTH: f32 = 2.0 / 16.0;
+//heights of the bottom and top of the two rails of A fence
+const FENCE_RAILS: [(f32, f32); 2] = [(6.0 / 16.0, 9.0 / 16.0), (12.0 / 16.0, 15.0 / 16.0)];
+
+//rectangle on A side of A block as (min u, min v, max u, max v), u and v are the two coordinates
+//along the side, in the same order as x, y and z
+pub type BlockFace = [f32; 4];
+pub const FULL_FACE: BlockFace = [0.0, 0.0, 1.0, 1.0];
+
+//axis aligned box within A block, coordinates go from 0.0 to 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockModel {
+    Cube,
+    //half A cube, the half of the state decides between the top and the bottom
+    Slab,
+    //A slab with A quarter block on top, facing the axis of the state
+    Stairs,
+    //two crossed quads, for plants
+    Cross,
+    //A thin wall along the axis of the state
+    Pane,
+    //A post with two rails along the axis of the state
+    Fence,
+    //A cube with its height depending on the fluid level of the state
+    Fluid,
+    Boxes(Vec<BlockBox>),
+}
+
+impl BlockBox {
+    pub const FULL: BlockBox = BlockBox {
+        min: [0.0, 0.0, 0.0],
+        max: [1.0, 1.0, 1.0],
+    };
+
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> BlockBox {
+        BlockBox { min, max }
+    }
+    //whether the box touches the given side of the block
+    pub fn touches(&self, side: Side) -> bool {
+        match side {
+            Side::Right => self.max[0] >= 1.0,
+            Side::Left => self.min[0] <= 0.0,
+            Side::Top => self.max[1] >= 1.0,
+            Side::Bot => self.min[1] <= 0.0,
+            Side::Back => self.max[2] >= 1.0,
+            Side::Front => self.min[2] <= 0.0,
+        }
+    }
+    //the part of the given side of the block covered by the box, None if it doesn't touch the side
+    pub fn get_face(&self, side: Side) -> Option<BlockFace> {
+        if !self.touches(side) {
+            return None;
+        }
+        let (u, v) = match side {
+            Side::Right | Side::Left => (1, 2),
+            Side::Top | Side::Bot => (0, 2),
+            Side::Back | Side::Front => (0, 1),
+        };
+        return Some([self.min[u], self.min[v], self.max[u], self.max[v]]);
+    }
+}
+
+fn contains_face(outer: &BlockFace, inner: &BlockFace) -> bool {
+    outer[0] <= inner[0] && outer[1] <= inner[1] && outer[2] >= inner[2] && outer[3] >= inner[3]
+}
+
+impl BlockModel {
+    pub fn get_boxes(&self, state: BlockState) -> Vec<BlockBox> {
+        match self {
+            BlockModel::Cube => vec![BlockBox::FULL],
+            BlockModel::Slab => match state.get_half() {
+                Half::Bottom => vec![BlockBox::new([0.0, 0.0, 0.0], [1.0, 0.5, 1.0])],
+                Half::Top => vec![BlockBox::new([0.0, 0.5, 0.0], [1.0, 1.0, 1.0])],
+            },
+            BlockModel::Stairs => {
+                let (base, step_y) = match state.get_half() {
+                    Half::Bottom => (BlockBox::new([0.0, 0.0, 0.0], [1.0, 0.5, 1.0]), 0.5),
+                    Half::Top => (BlockBox::new([0.0, 0.5, 0.0], [1.0, 1.0, 1.0]), 0.0),
+                };
+                let step = match state.get_axis() {
+                    Axis::X => BlockBox::new([0.5, step_y, 0.0], [1.0, step_y + 0.5, 1.0]),
+                    _ => BlockBox::new([0.0, step_y, 0.5], [1.0, step_y + 0.5, 1.0]),
+                };
+                vec![base, step]
+            }
+            BlockModel::Cross => Vec::new(),
+            BlockModel::Pane => {
+                let min = 0.5 - PANE_WIDTH / 2.0;
+                let max = 0.5 + PANE_WIDTH / 2.0;
+                match state.get_axis() {
+                    Axis::Z => vec![BlockBox::new([min, 0.0, 0.0], [max, 1.0, 1.0])],
+                    _ => vec![BlockBox::new([0.0, 0.0, min], [1.0, 1.0, max])],
+                }
+            }
+            BlockModel::Fence => {
+                let post_min = 0.5 - FENCE_POST_WIDTH / 2.0;
+                let post_max = 0.5 + FENCE_POST_WIDTH / 2.0;
+                let rail_min = 0.5 - FENCE_RAIL_WIDTH / 2.0;
+                let rail_max = 0.5 + FENCE_RAIL_WIDTH / 2.0;
+                let mut boxes = vec![BlockBox::new(
+                    [post_min, 0.0, post_min],
+                    [post_max, 1.0, post_max],
+                )];
+                for &(bottom, top) in FENCE_RAILS.iter() {
+                    boxes.push(match state.get_axis() {
+                        Axis::Z => BlockBox::new([rail_min, bottom, 0.0], [rail_max, top, 1.0]),
+                        _ => BlockBox::new([0.0, bottom, rail_min], [1.0, top, rail_max]),
+                    });
+                }
+                boxes
+            }
+            BlockModel::Fluid => {
+                let height = state.get_fluid_level() as f32 / MAX_FLUID_LEVEL as f32;
+                vec![BlockBox::new([0.0, 0.0, 0.0], [1.0, height, 1.0])]
+            }
+            BlockModel::Boxes(boxes) => boxes.clone(),
+        }
+    }
+    //the faces of the model on the given side of the block
+    pub fn get_faces(&self, state: BlockState, side: Side) -> Vec<BlockFace> {
+        match self {
+            BlockModel::Cube => vec![FULL_FACE],
+            BlockModel::Cross => Vec::new(),
+            _ => self
+                .get_boxes(state)
+                .iter()
+                .filter_map(|b| b.get_face(side))
+                .collect(),
+        }
+    }
+    //whether the faces of the model on the given side hide all of the face of A neighbor
+    pub fn covers_face(&self, state: BlockState, side: Side, face: &BlockFace) -> bool {
+        self.get_faces(state, side)
+            .iter()
+            .any(|f| contains_face(f, face))
+    }
+}
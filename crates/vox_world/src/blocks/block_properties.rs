@@ -0,0 +1,74 @@
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockProperties {
+    //entities can not move through collidable blocks
+    pub collidable: bool,
+    //faces of neighboring blocks are hidden behind blocks which occlude
+    pub occludes: bool,
+    //how much light is absorbed when passing through, MAX_LIGHT_LEVEL blocks all light
+    pub light_opacity: u8,
+    //light level given off by the block itself
+    pub light_emission: u8,
+    //world generation and block placement may overwrite replaceable blocks
+    pub replaceable: bool,
+    pub fluid: bool,
+    //world generation places trees and plants on top of the highest ground block
+    pub ground: bool,
+}
+
+impl BlockProperties {
+    pub fn solid() -> BlockProperties {
+        BlockProperties {
+            collidable: true,
+            occludes: true,
+            light_opacity: MAX_LIGHT_LEVEL,
+            light_emission: 0,
+            replaceable: false,
+            fluid: false,
+            ground: false,
+        }
+    }
+    pub fn empty() -> BlockProperties {
+        BlockProperties {
+            collidable: false,
+            occludes: false,
+            light_opacity: 0,
+            light_emission: 0,
+            replaceable: true,
+            fluid: false,
+            ground: false,
+        }
+    }
+    pub fn fluid() -> BlockProperties {
+        BlockProperties {
+            collidable: false,
+            occludes: false,
+            light_opacity: 2,
+            light_emission: 0,
+            replaceable: true,
+            fluid: true,
+            ground: false,
+        }
+    }
+    //solid blocks which can be seen through, like leaves or glass
+    pub fn see_through() -> BlockProperties {
+        BlockProperties {
+            occludes: false,
+            light_opacity: 1,
+            ..BlockProperties::solid()
+        }
+    }
+    pub fn with_light_emission(mut self, light_emission: u8) -> BlockProperties {
+        self.light_emission = light_emission.min(MAX_LIGHT_LEVEL);
+        self
+    }
+    pub fn with_light_opacity(mut self, light_opacity: u8) -> BlockProperties {
+        self.light_opacity = light_opacity.min(MAX_LIGHT_LEVEL);
+        self
+    }
+    pub fn with_ground(mut self, ground: bool) -> BlockProperties {
+        self.ground = ground;
+        self
+    }
+}
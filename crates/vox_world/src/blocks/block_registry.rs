@@ -0,0 +1,301 @@
+use crate::blocks::block::{
+    BlockId, AIR, DIRT, FENCE, GLASS_PANE, GRASS, LEAF, SAND, STONE, STONE_SLAB, STONE_STAIRS,
+    TALL_GRASS, UNKNOWN, WATER,
+};
+use crate::blocks::block_model::BlockModel;
+use crate::blocks::block_properties::BlockProperties;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use vox_core::constants::BLOCK_COLOR_COUNT;
+use vox_render::renderer::vertex::NO_TEXTURE;
+
+//color used for blocks which have no color of their own, like textured blocks
+const MISSING_COLOR: [f32; 4] = [255.0, 0.0, 255.0, 255.0];
+
+static BLOCK_REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+
+//paths to the image files used for the faces of A block
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockTextures {
+    pub top: String,
+    pub side: String,
+    pub bottom: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureFace {
+    Top,
+    Side,
+    Bottom,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockAppearance {
+    Color([f32; 4]),
+    Texture(BlockTextures),
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockDefinition {
+    pub id: BlockId,
+    pub name: String,
+    pub appearance: BlockAppearance,
+    pub model: BlockModel,
+    pub properties: BlockProperties,
+    //transparent blocks are drawn after all opaque blocks so they can be blended
+    pub transparent: bool,
+    //vertices of animated blocks are moved around by the vertex shader
+    pub animated: bool,
+    //the top and bottom textures face along the axis of the state instead of up and down, like
+    //for logs. Other blocks may use the axis bits for their shape, their textures stay upright
+    pub rotates_textures: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BlockRegistryError {
+    IdTaken(BlockId),
+    NameTaken(String),
+}
+
+pub struct BlockRegistry {
+    blocks: Vec<Option<BlockDefinition>>,
+    names: HashMap<String, BlockId>,
+    unknown: BlockDefinition,
+    //every texture path gets one layer in the texture array, in this order
+    textures: Vec<String>,
+    texture_layers: Vec<[u32; 3]>,
+}
+
+impl BlockTextures {
+    pub fn new(top: &str, side: &str, bottom: &str) -> BlockTextures {
+        BlockTextures {
+            top: top.to_string(),
+            side: side.to_string(),
+            bottom: bottom.to_string(),
+        }
+    }
+    pub fn all(path: &str) -> BlockTextures {
+        BlockTextures::new(path, path, path)
+    }
+}
+
+impl BlockDefinition {
+    pub fn new(id: BlockId, name: &str, appearance: BlockAppearance) -> BlockDefinition {
+        BlockDefinition {
+            id,
+            name: name.to_string(),
+            appearance,
+            model: BlockModel::Cube,
+            properties: BlockProperties::solid(),
+            transparent: false,
+            animated: false,
+            rotates_textures: false,
+        }
+    }
+    pub fn model(mut self, model: BlockModel) -> BlockDefinition {
+        self.model = model;
+        self
+    }
+    pub fn properties(mut self, properties: BlockProperties) -> BlockDefinition {
+        self.properties = properties;
+        self
+    }
+    pub fn transparent(mut self, transparent: bool) -> BlockDefinition {
+        self.transparent = transparent;
+        self
+    }
+    pub fn animated(mut self, animated: bool) -> BlockDefinition {
+        self.animated = animated;
+        self
+    }
+    pub fn rotates_textures(mut self, rotates_textures: bool) -> BlockDefinition {
+        self.rotates_textures = rotates_textures;
+        self
+    }
+    pub fn get_color(&self) -> [f32; 4] {
+        match &self.appearance {
+            BlockAppearance::Color(color) => *color,
+            BlockAppearance::Texture(_) => MISSING_COLOR,
+        }
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> BlockRegistry {
+        BlockRegistry::new()
+    }
+}
+
+impl BlockRegistry {
+    pub fn new() -> BlockRegistry {
+        BlockRegistry {
+            blocks: vec![None; BLOCK_COLOR_COUNT],
+            names: HashMap::new(),
+            unknown: BlockDefinition::new(UNKNOWN, "unknown", BlockAppearance::Color([0.0; 4]))
+                .properties(BlockProperties::empty())
+                .transparent(true),
+            textures: Vec::new(),
+            texture_layers: vec![[NO_TEXTURE; 3]; BLOCK_COLOR_COUNT],
+        }
+    }
+    pub fn with_default_blocks() -> BlockRegistry {
+        let mut registry = BlockRegistry::new();
+        let defaults = vec![
+            BlockDefinition::new(
+                GRASS,
+                "grass",
+                BlockAppearance::Color([0.0, 255.0, 0.0, 255.0]),
+            )
+            .properties(BlockProperties::solid().with_ground(true)),
+            BlockDefinition::new(
+                WATER,
+                "water",
+                BlockAppearance::Color([0.0, 0.0, 255.0, 128.0]),
+            )
+            .model(BlockModel::Fluid)
+            .properties(BlockProperties::fluid().with_ground(true))
+            .transparent(true),
+            BlockDefinition::new(
+                DIRT,
+                "dirt",
+                BlockAppearance::Color([255.0, 64.0, 64.0, 255.0]),
+            )
+            .properties(BlockProperties::solid().with_ground(true)),
+            BlockDefinition::new(
+                STONE,
+                "stone",
+                BlockAppearance::Color([128.0, 128.0, 128.0, 255.0]),
+            )
+            .properties(BlockProperties::solid().with_ground(true)),
+            BlockDefinition::new(
+                SAND,
+                "sand",
+                BlockAppearance::Color([255.0, 0.0, 0.0, 255.0]),
+            ),
+            BlockDefinition::new(AIR, "air", BlockAppearance::Color([255.0, 0.0, 255.0, 0.0]))
+                .properties(BlockProperties::empty())
+                .transparent(true),
+            BlockDefinition::new(
+                LEAF,
+                "leaf",
+                BlockAppearance::Color([0.0, 128.0, 0.0, 254.99]),
+            )
+            .properties(BlockProperties {
+                collidable: false,
+                ..BlockProperties::see_through()
+            })
+            .animated(true),
+            BlockDefinition::new(
+                STONE_SLAB,
+                "stone_slab",
+                BlockAppearance::Color([128.0, 128.0, 128.0, 255.0]),
+            )
+            .model(BlockModel::Slab),
+            BlockDefinition::new(
+                STONE_STAIRS,
+                "stone_stairs",
+                BlockAppearance::Color([128.0, 128.0, 128.0, 255.0]),
+            )
+            .model(BlockModel::Stairs),
+            BlockDefinition::new(
+                TALL_GRASS,
+                "tall_grass",
+                BlockAppearance::Color([0.0, 200.0, 0.0, 255.0]),
+            )
+            .model(BlockModel::Cross)
+            .properties(BlockProperties::empty()),
+            BlockDefinition::new(
+                GLASS_PANE,
+                "glass_pane",
+                BlockAppearance::Color([200.0, 220.0, 255.0, 96.0]),
+            )
+            .model(BlockModel::Pane)
+            .properties(BlockProperties::see_through())
+            .transparent(true),
+            BlockDefinition::new(
+                FENCE,
+                "fence",
+                BlockAppearance::Color([160.0, 110.0, 60.0, 255.0]),
+            )
+            .model(BlockModel::Fence)
+            .properties(BlockProperties::see_through()),
+        ];
+        for block in defaults {
+            registry.register(block).unwrap();
+        }
+        return registry;
+    }
+
+    pub fn register(&mut self, block: BlockDefinition) -> Result<(), BlockRegistryError> {
+        if block.id == UNKNOWN || self.blocks[block.id as usize].is_some() {
+            return Err(BlockRegistryError::IdTaken(block.id));
+        }
+        if self.names.contains_key(&block.name) {
+            return Err(BlockRegistryError::NameTaken(block.name));
+        }
+        self.names.insert(block.name.clone(), block.id);
+        let id = block.id as usize;
+        if let BlockAppearance::Texture(textures) = &block.appearance {
+            self.texture_layers[id] = [
+                self.get_or_add_texture(&textures.top),
+                self.get_or_add_texture(&textures.side),
+                self.get_or_add_texture(&textures.bottom),
+            ];
+        }
+        self.blocks[id] = Some(block);
+        Ok(())
+    }
+    #[inline]
+    pub fn get(&self, id: BlockId) -> &BlockDefinition {
+        match &self.blocks[id as usize] {
+            Some(block) => block,
+            None => &self.unknown,
+        }
+    }
+    pub fn get_texture_layer(&self, id: BlockId, face: TextureFace) -> u32 {
+        let layers = &self.texture_layers[id as usize];
+        match face {
+            TextureFace::Top => layers[0],
+            TextureFace::Side => layers[1],
+            TextureFace::Bottom => layers[2],
+        }
+    }
+    pub fn get_texture_paths(&self) -> &[String] {
+        &self.textures
+    }
+    fn get_or_add_texture(&mut self, path: &str) -> u32 {
+        match self.textures.iter().position(|t| t == path) {
+            Some(layer) => layer as u32,
+            None => {
+                self.textures.push(path.to_string());
+                (self.textures.len() - 1) as u32
+            }
+        }
+    }
+    pub fn get_id(&self, name: &str) -> Option<BlockId> {
+        self.names.get(name).copied()
+    }
+    pub fn is_registered(&self, id: BlockId) -> bool {
+        self.blocks[id as usize].is_some()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &BlockDefinition> {
+        self.blocks.iter().filter_map(|b| b.as_ref())
+    }
+    pub fn get_colors(&self) -> [[f32; 4]; BLOCK_COLOR_COUNT] {
+        let mut colors = [[0.0; 4]; BLOCK_COLOR_COUNT];
+        for block in self.iter() {
+            colors[block.id as usize] = block.get_color();
+        }
+        return colors;
+    }
+}
+
+//installs the registry used by the whole game, this only works before the registry is first used
+pub fn set_block_registry(registry: BlockRegistry) -> Result<(), Box<BlockRegistry>> {
+    BLOCK_REGISTRY.set(registry).map_err(Box::new)
+}
+
+#[inline]
+pub fn get_block_registry() -> &'static BlockRegistry {
+    BLOCK_REGISTRY.get_or_init(BlockRegistry::with_default_blocks)
+}
@@ -0,0 +1,90 @@
+use crate::blocks::block::BlockId;
+use serde::{Deserialize, Serialize};
+
+//the meaning of the data bits depends on the block, A block only uses the properties that apply to it
+const AXIS_MASK: u8 = 0b0000_0011;
+const HALF_MASK: u8 = 0b0000_0100;
+const LEVEL_MASK: u8 = 0b0111_1000;
+const LEVEL_SHIFT: u8 = 3;
+//fluids with this level are source blocks and fill the whole block
+pub const MAX_FLUID_LEVEL: u8 = 8;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BlockState {
+    pub id: BlockId,
+    pub data: u8,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Half {
+    Bottom,
+    Top,
+}
+
+impl BlockState {
+    pub fn new(id: BlockId) -> BlockState {
+        BlockState { id, data: 0 }
+    }
+    pub fn get_axis(&self) -> Axis {
+        match self.data & AXIS_MASK {
+            1 => Axis::X,
+            2 => Axis::Z,
+            _ => Axis::Y,
+        }
+    }
+    pub fn with_axis(&self, axis: Axis) -> BlockState {
+        let bits = match axis {
+            Axis::Y => 0,
+            Axis::X => 1,
+            Axis::Z => 2,
+        };
+        BlockState {
+            id: self.id,
+            data: (self.data & !AXIS_MASK) | bits,
+        }
+    }
+    pub fn get_half(&self) -> Half {
+        if self.data & HALF_MASK == 0 {
+            Half::Bottom
+        } else {
+            Half::Top
+        }
+    }
+    pub fn with_half(&self, half: Half) -> BlockState {
+        let bits = match half {
+            Half::Bottom => 0,
+            Half::Top => HALF_MASK,
+        };
+        BlockState {
+            id: self.id,
+            data: (self.data & !HALF_MASK) | bits,
+        }
+    }
+    //A level of 0 is stored for fresh blocks, which is treated as A full source block
+    pub fn get_fluid_level(&self) -> u8 {
+        match (self.data & LEVEL_MASK) >> LEVEL_SHIFT {
+            0 => MAX_FLUID_LEVEL,
+            level => level.min(MAX_FLUID_LEVEL),
+        }
+    }
+    pub fn with_fluid_level(&self, level: u8) -> BlockState {
+        let level = level.clamp(1, MAX_FLUID_LEVEL);
+        BlockState {
+            id: self.id,
+            data: (self.data & !LEVEL_MASK) | (level << LEVEL_SHIFT),
+        }
+    }
+}
+
+impl From<BlockId> for BlockState {
+    fn from(id: BlockId) -> BlockState {
+        BlockState::new(id)
+    }
+}
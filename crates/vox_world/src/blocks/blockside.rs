@@ -27,6 +27,16 @@ impl BlockSides {
         self.front = b;
         self.back = b;
     }
+    pub fn get(&self, side: Side) -> bool {
+        match side {
+            Side::Top => self.top,
+            Side::Bot => self.bot,
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Front => self.front,
+            Side::Back => self.back,
+        }
+    }
     pub fn is_all(&mut self, b: bool) -> bool {
         return self.left == b
             && self.right == b
@@ -36,3 +46,26 @@ impl BlockSides {
             && self.top == b;
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Bot,
+    Left,
+    Right,
+    Front,
+    Back,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Top => Side::Bot,
+            Side::Bot => Side::Top,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Front => Side::Back,
+            Side::Back => Side::Front,
+        }
+    }
+}
@@ -1,4 +1,7 @@
 pub mod block;
 mod block_mesh;
-pub mod block_type;
+pub mod block_model;
+pub mod block_properties;
+pub mod block_registry;
+pub mod block_state;
 pub mod blockside;
@@ -1,8 +1,8 @@
-use crate::world::small_world::SmallWorld;
+use crate::world::voxel_world::VoxelWorld;
 use crate::world_gen::vertex_generation::get_chunk_vertices;
 use std::time::Instant;
 use vox_core::positions::ChunkPos;
-use vox_render::renderer::vertex::Vertex;
+use vox_render::renderer::vertex::{ChunkOrigin, Vertex};
 use wgpu::util::DeviceExt;
 use wgpu::{Device, RenderPass};
 
@@ -11,10 +11,11 @@ pub struct ChunkRenderData {
     pub num_vertices: Option<u32>,
     pub index_buffer: Option<wgpu::Buffer>,
     pub num_indices: Option<u32>,
+    pub origin_buffer: Option<wgpu::Buffer>,
 }
 
 impl ChunkRenderData {
-    pub fn new(world: &SmallWorld, chunk_pos: &ChunkPos, device: &Device) -> ChunkRenderData {
+    pub fn new(world: &impl VoxelWorld, chunk_pos: &ChunkPos, device: &Device) -> ChunkRenderData {
         let timer = Instant::now();
         let (vertices, indices) = get_chunk_vertices(world, &chunk_pos);
         if vertices.len() == 0 {
@@ -23,6 +24,7 @@ impl ChunkRenderData {
                 num_vertices: None,
                 index_buffer: None,
                 num_indices: None,
+                origin_buffer: None,
             };
         }
         println!("vertex gen time: {}", timer.elapsed().as_micros());
@@ -40,6 +42,13 @@ impl ChunkRenderData {
             contents: bytemuck::cast_slice(indices),
             usage: wgpu::BufferUsage::INDEX,
         });
+        let origin_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Chunk Origin Buffer"),
+            contents: bytemuck::cast_slice(&[ChunkOrigin {
+                _chunk: [chunk_pos.x, chunk_pos.y, chunk_pos.z],
+            }]),
+            usage: wgpu::BufferUsage::VERTEX,
+        });
         let num_indices = indices.len() as u32;
         let num_vertices = vertices.len() as u32;
 
@@ -48,11 +57,13 @@ impl ChunkRenderData {
             num_vertices: Some(num_vertices),
             index_buffer: Some(index_buffer),
             num_indices: Some(num_indices),
+            origin_buffer: Some(origin_buffer),
         }
     }
     pub fn do_render_pass<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
         if self.num_indices.is_some() {
             render_pass.set_vertex_buffer(0, self.vertex_buffer.as_ref().unwrap().slice(..));
+            render_pass.set_vertex_buffer(1, self.origin_buffer.as_ref().unwrap().slice(..));
             render_pass.set_index_buffer(
                 self.index_buffer.as_ref().unwrap().slice(..),
                 wgpu::IndexFormat::Uint32,
@@ -1,6 +1,8 @@
-mod algorithms;
-mod blocks;
+pub mod algorithms;
+pub mod blocks;
 pub mod chunk_render_data;
+pub mod movement;
+pub mod physics;
 pub mod player;
 mod structures;
 pub mod world;
@@ -0,0 +1,80 @@
+//how A player moves through the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MovementMode {
+    //gravity and collision, space jumps
+    Walk,
+    //no gravity, space and left shift move up and down, blocks still collide
+    Fly,
+    //like fly, but passes through blocks
+    Spectator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovementSettings {
+    //blocks per second
+    pub speed: f64,
+    //change in velocity per second while moving
+    pub acceleration: f64,
+    //change in velocity per second while no direction is held
+    pub friction: f64,
+    pub falls: bool,
+    pub collides: bool,
+    //whether the player controls the vertical velocity, otherwise only gravity and jumping do
+    pub vertical_control: bool,
+}
+
+impl MovementMode {
+    pub fn get_settings(&self) -> MovementSettings {
+        return match self {
+            MovementMode::Walk => MovementSettings {
+                speed: 5.0,
+                acceleration: 50.0,
+                friction: 40.0,
+                falls: true,
+                collides: true,
+                vertical_control: false,
+            },
+            MovementMode::Fly => MovementSettings {
+                speed: 12.0,
+                acceleration: 60.0,
+                friction: 30.0,
+                falls: false,
+                collides: true,
+                vertical_control: true,
+            },
+            MovementMode::Spectator => MovementSettings {
+                speed: 40.0,
+                acceleration: 200.0,
+                friction: 200.0,
+                falls: false,
+                collides: false,
+                vertical_control: true,
+            },
+        };
+    }
+    //the mode the mode switch key goes to
+    pub fn next(&self) -> MovementMode {
+        return match self {
+            MovementMode::Walk => MovementMode::Fly,
+            MovementMode::Fly => MovementMode::Spectator,
+            MovementMode::Spectator => MovementMode::Walk,
+        };
+    }
+}
+
+//moves the velocity towards the target, by at most rate * dt. Only the axes in use are changed
+pub fn accelerate(velocity: &mut [f64; 3], target: [f64; 3], axes: &[usize], rate: f64, dt: f64) {
+    let difference: Vec<f64> = axes.iter().map(|&a| target[a] - velocity[a]).collect();
+    let length = difference.iter().map(|d| d * d).sum::<f64>().sqrt();
+    let max_change = rate * dt;
+    if length <= max_change {
+        for &axis in axes {
+            velocity[axis] = target[axis];
+        }
+        return;
+    }
+    let scale = max_change / length;
+    for (&axis, d) in axes.iter().zip(difference.iter()) {
+        velocity[axis] += d * scale;
+    }
+}
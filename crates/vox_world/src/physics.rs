@@ -0,0 +1,612 @@
+use crate::blocks::block_registry::get_block_registry;
+use crate::world::voxel_world::VoxelWorld;
+use vox_core::positions::{GlobalBlockPos, ObjectPos, ToChunkPos};
+
+//all values are in blocks and seconds
+pub const GRAVITY: f64 = 32.0;
+pub const TERMINAL_VELOCITY: f64 = 78.0;
+pub const JUMP_VELOCITY: f64 = 9.0;
+//obstacles up to this height are walked onto instead of blocking the body
+pub const STEP_HEIGHT: f64 = 1.0;
+//how fast the drawn height of A body catches up after it stepped onto something
+pub const STEP_SMOOTHING: f64 = 8.0;
+//gap kept between A body and the blocks it touches, so it never ends up inside them
+const SKIN: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl Aabb {
+    pub fn new(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb { min, max }
+    }
+    pub fn offset(&self, by: [f64; 3]) -> Aabb {
+        Aabb {
+            min: [
+                self.min[0] + by[0],
+                self.min[1] + by[1],
+                self.min[2] + by[2],
+            ],
+            max: [
+                self.max[0] + by[0],
+                self.max[1] + by[1],
+                self.max[2] + by[2],
+            ],
+        }
+    }
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|axis| self.min[axis] < other.max[axis] && self.max[axis] > other.min[axis])
+    }
+    //the box grown in the direction of the motion, covering everything it sweeps through
+    pub fn expand(&self, motion: [f64; 3]) -> Aabb {
+        let mut expanded = *self;
+        for (axis, &distance) in motion.iter().enumerate() {
+            if distance < 0.0 {
+                expanded.min[axis] += distance;
+            } else {
+                expanded.max[axis] += distance;
+            }
+        }
+        return expanded;
+    }
+    //the box grown by the same distance on every side
+    pub fn grow(&self, by: f64) -> Aabb {
+        Aabb {
+            min: [self.min[0] - by, self.min[1] - by, self.min[2] - by],
+            max: [self.max[0] + by, self.max[1] + by, self.max[2] + by],
+        }
+    }
+    //every block cell the box overlaps
+    pub fn get_blocks(&self) -> Vec<GlobalBlockPos> {
+        let min: Vec<i32> = self.min.iter().map(|v| v.floor() as i32).collect();
+        let max: Vec<i32> = self.max.iter().map(|v| v.ceil() as i32).collect();
+        let mut blocks = Vec::new();
+        for x in min[0]..max[0] {
+            for y in min[1]..max[1] {
+                for z in min[2]..max[2] {
+                    blocks.push(GlobalBlockPos { x, y, z });
+                }
+            }
+        }
+        return blocks;
+    }
+}
+
+//the boxes of collidable blocks the given box overlaps. Unloaded blocks within the height of the
+//world are solid, so nothing falls through the world before it is generated
+pub fn get_collision_boxes(world: &impl VoxelWorld, area: &Aabb) -> Vec<Aabb> {
+    let registry = get_block_registry();
+    let mut boxes = Vec::new();
+    for pos in area.get_blocks() {
+        let origin = [pos.x as f64, pos.y as f64, pos.z as f64];
+        match world.get_block_state(&pos) {
+            Some(state) => {
+                let definition = registry.get(state.id);
+                if !definition.properties.collidable {
+                    continue;
+                }
+                for b in definition.model.get_boxes(state) {
+                    boxes.push(Aabb::new(
+                        [
+                            origin[0] + b.min[0] as f64,
+                            origin[1] + b.min[1] as f64,
+                            origin[2] + b.min[2] as f64,
+                        ],
+                        [
+                            origin[0] + b.max[0] as f64,
+                            origin[1] + b.max[1] as f64,
+                            origin[2] + b.max[2] as f64,
+                        ],
+                    ));
+                }
+            }
+            None => {
+                if pos.y >= world.get_min_y() && pos.y < world.get_max_y() {
+                    boxes.push(Aabb::new(
+                        origin,
+                        [origin[0] + 1.0, origin[1] + 1.0, origin[2] + 1.0],
+                    ));
+                }
+            }
+        }
+    }
+    return boxes;
+}
+
+//whether every chunk the box overlaps is loaded
+pub fn is_loaded(world: &impl VoxelWorld, area: &Aabb) -> bool {
+    return area.get_blocks().iter().all(|pos| {
+        pos.y < world.get_min_y()
+            || pos.y >= world.get_max_y()
+            || world.get_chunk(&pos.get_chunk_pos()).is_some()
+    });
+}
+
+//how far the box can move along one axis before it touches one of the boxes
+fn clip_axis(moving: &Aabb, boxes: &[Aabb], axis: usize, mut distance: f64) -> f64 {
+    let others = [(axis + 1) % 3, (axis + 2) % 3];
+    for b in boxes {
+        let overlaps = others
+            .iter()
+            .all(|&o| moving.min[o] < b.max[o] && moving.max[o] > b.min[o]);
+        if !overlaps {
+            continue;
+        }
+        if distance > 0.0 && b.min[axis] >= moving.max[axis] - SKIN {
+            distance = distance.min(b.min[axis] - moving.max[axis] - SKIN).max(0.0);
+        } else if distance < 0.0 && b.max[axis] <= moving.min[axis] + SKIN {
+            distance = distance.max(b.max[axis] - moving.min[axis] + SKIN).min(0.0);
+        }
+    }
+    return distance;
+}
+
+//moves the box one axis at A time, y first, and returns how far it actually moved
+pub fn sweep(world: &impl VoxelWorld, aabb: &Aabb, motion: [f64; 3]) -> [f64; 3] {
+    //A motion ending exactly on A block border still has to see the block behind it, rounding
+    //can put the box A tiny bit further than it moved
+    let boxes = get_collision_boxes(world, &aabb.expand(motion).grow(SKIN));
+    let mut moving = *aabb;
+    let mut moved = [0.0; 3];
+    for &axis in [1, 0, 2].iter() {
+        moved[axis] = clip_axis(&moving, &boxes, axis, motion[axis]);
+        let mut offset = [0.0; 3];
+        offset[axis] = moved[axis];
+        moving = moving.offset(offset);
+    }
+    return moved;
+}
+
+//A kinematic body, the position it belongs to is kept by its owner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    //collision box relative to the position of the body
+    pub shape: Aabb,
+    //blocks per second
+    pub velocity: [f64; 3],
+    pub on_ground: bool,
+    //gravity is only applied to bodies that fall
+    pub falls: bool,
+    //bodies that don't collide move freely through blocks and unloaded chunks
+    pub collides: bool,
+    //how far below its position the body should be drawn. Steps move the body up at once, this
+    //lets the drawn height follow over A few ticks
+    pub step_offset: f64,
+}
+
+impl Body {
+    pub fn new(shape: Aabb) -> Body {
+        Body {
+            shape,
+            velocity: [0.0; 3],
+            on_ground: false,
+            falls: true,
+            collides: true,
+            step_offset: 0.0,
+        }
+    }
+    pub fn get_aabb(&self, pos: &ObjectPos) -> Aabb {
+        return self.shape.offset([pos.x, pos.y, pos.z]);
+    }
+    //only has an effect while standing on something
+    pub fn jump(&mut self) {
+        if self.on_ground {
+            self.velocity[1] = JUMP_VELOCITY;
+            self.on_ground = false;
+        }
+    }
+    //applies gravity and velocity for one tick. Nothing happens while A colliding body overlaps
+    //unloaded chunks, and A colliding body stuck inside blocks is moved out of them instead
+    pub fn update(&mut self, pos: &mut ObjectPos, world: &impl VoxelWorld, dt: f64) {
+        self.step_offset = (self.step_offset + STEP_SMOOTHING * dt).min(0.0);
+        if !self.collides {
+            pos.x += self.velocity[0] * dt;
+            pos.y += self.velocity[1] * dt;
+            pos.z += self.velocity[2] * dt;
+            self.on_ground = false;
+            return;
+        }
+        let aabb = self.get_aabb(pos);
+        if !is_loaded(world, &aabb) {
+            return;
+        }
+        if let Some(push) = get_push_out(world, &aabb) {
+            pos.x += push[0];
+            pos.y += push[1];
+            pos.z += push[2];
+            for (velocity, distance) in self.velocity.iter_mut().zip(push.iter()) {
+                if *distance != 0.0 {
+                    *velocity = 0.0;
+                }
+            }
+            if push[1] > 0.0 {
+                self.step_offset = (self.step_offset - push[1]).max(-STEP_HEIGHT);
+            }
+            self.on_ground = push[1] > 0.0;
+            return;
+        }
+        if self.falls {
+            self.velocity[1] = (self.velocity[1] - GRAVITY * dt).max(-TERMINAL_VELOCITY);
+        }
+        let motion = [
+            self.velocity[0] * dt,
+            self.velocity[1] * dt,
+            self.velocity[2] * dt,
+        ];
+        self.move_by(pos, world, motion);
+    }
+    //moves through the world, stepping onto low obstacles while on the ground. Velocity along an
+    //axis that was blocked is removed
+    pub fn move_by(
+        &mut self,
+        pos: &mut ObjectPos,
+        world: &impl VoxelWorld,
+        motion: [f64; 3],
+    ) -> [f64; 3] {
+        let aabb = self.get_aabb(pos);
+        let mut moved = sweep(world, &aabb, motion);
+        let blocked_horizontally = moved[0] != motion[0] || moved[2] != motion[2];
+        if self.on_ground && blocked_horizontally && motion[1] <= 0.0 {
+            let stepped = step_up(world, &aabb, motion);
+            if horizontal_length(stepped) > horizontal_length(moved) {
+                self.step_offset =
+                    (self.step_offset - (stepped[1] - moved[1]).max(0.0)).max(-STEP_HEIGHT);
+                moved = stepped;
+            }
+        }
+        pos.x += moved[0];
+        pos.y += moved[1];
+        pos.z += moved[2];
+
+        for axis in 0..3 {
+            if moved[axis] != motion[axis] {
+                self.velocity[axis] = 0.0;
+            }
+        }
+        self.on_ground = motion[1] < 0.0 && moved[1] != motion[1]
+            || motion[1] == 0.0 && self.is_standing(pos, world);
+        return moved;
+    }
+    pub fn is_standing(&self, pos: &ObjectPos, world: &impl VoxelWorld) -> bool {
+        let aabb = self.get_aabb(pos);
+        return sweep(world, &aabb, [0.0, -SKIN * 4.0, 0.0])[1] > -SKIN * 4.0;
+    }
+}
+
+//the shortest move along A single axis that gets the box out of the blocks it overlaps, None if
+//it doesn't overlap any. Blocks low enough to step onto lift the box onto them, and moves which end
+//inside other blocks are only used when every move does
+fn get_push_out(world: &impl VoxelWorld, aabb: &Aabb) -> Option<[f64; 3]> {
+    //overlaps thinner than the skin come from rounding the position and only count as touching
+    let inner = aabb.grow(-SKIN);
+    let overlapping: Vec<Aabb> = get_collision_boxes(world, &inner)
+        .into_iter()
+        .filter(|b| b.intersects(&inner))
+        .collect();
+    if overlapping.is_empty() {
+        return None;
+    }
+    let mut moves = Vec::with_capacity(6);
+    for axis in 0..3 {
+        let highest = overlapping
+            .iter()
+            .map(|b| b.max[axis])
+            .fold(f64::MIN, f64::max);
+        let lowest = overlapping
+            .iter()
+            .map(|b| b.min[axis])
+            .fold(f64::MAX, f64::min);
+        let mut forward = [0.0; 3];
+        forward[axis] = highest - aabb.min[axis] + SKIN;
+        let mut backward = [0.0; 3];
+        backward[axis] = lowest - aabb.max[axis] - SKIN;
+        moves.push(forward);
+        moves.push(backward);
+    }
+    let cost = |push: &[f64; 3]| {
+        if push[1] > 0.0 && push[1] <= STEP_HEIGHT {
+            return 0.0;
+        }
+        return push.iter().map(|v| v.abs()).sum::<f64>();
+    };
+    moves.sort_by(|a, b| cost(a).partial_cmp(&cost(b)).unwrap());
+    let is_free = |push: &[f64; 3]| {
+        let moved = aabb.offset(*push).grow(-SKIN);
+        return !get_collision_boxes(world, &moved)
+            .iter()
+            .any(|b| b.intersects(&moved));
+    };
+    return Some(*moves.iter().find(|push| is_free(push)).unwrap_or(&moves[0]));
+}
+
+//lifts the box by STEP_HEIGHT, moves it horizontally and lowers it back onto what it stepped on
+fn step_up(world: &impl VoxelWorld, aabb: &Aabb, motion: [f64; 3]) -> [f64; 3] {
+    let up = sweep(world, aabb, [0.0, STEP_HEIGHT, 0.0])[1];
+    let raised = aabb.offset([0.0, up, 0.0]);
+    let horizontal = sweep(world, &raised, [motion[0], 0.0, motion[2]]);
+    let moved = raised.offset(horizontal);
+    let down = sweep(world, &moved, [0.0, -up + motion[1], 0.0])[1];
+    return [horizontal[0], up + down, horizontal[2]];
+}
+
+fn horizontal_length(motion: [f64; 3]) -> f64 {
+    return (motion[0] * motion[0] + motion[2] * motion[2]).sqrt();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::block::{AIR, STONE, STONE_SLAB};
+    use crate::blocks::block_state::BlockState;
+    use crate::world_gen::chunk::Chunk;
+    use std::collections::HashMap;
+    use vox_core::positions::ChunkPos;
+    use vox_core::world_config::get_world_config;
+
+    const DT: f64 = 0.05;
+    //top of the floor every test stands on
+    const FLOOR_Y: i32 = 10;
+
+    //A single loaded column of chunks, empty except for the blocks placed by the test
+    struct TestWorld {
+        chunks: HashMap<ChunkPos, Chunk>,
+    }
+
+    impl TestWorld {
+        fn new() -> TestWorld {
+            let mut chunks = HashMap::new();
+            for y in 0..get_world_config().max_chunk_y {
+                chunks.insert(ChunkPos { x: 0, y, z: 0 }, Chunk::new(BlockState::new(AIR)));
+            }
+            return TestWorld { chunks };
+        }
+        //A stone floor ending at FLOOR_Y
+        fn with_floor() -> TestWorld {
+            let mut world = TestWorld::new();
+            for x in 0..16 {
+                for z in 0..16 {
+                    world.set_block(
+                        &GlobalBlockPos {
+                            x,
+                            y: FLOOR_Y - 1,
+                            z,
+                        },
+                        STONE,
+                    );
+                }
+            }
+            return world;
+        }
+        //raises the floor by the given height from the given x to its end
+        fn place_ledge(&mut self, from_x: i32, height: i32) {
+            for x in from_x..16 {
+                for y in FLOOR_Y..FLOOR_Y + height {
+                    for z in 0..16 {
+                        self.set_block(&GlobalBlockPos { x, y, z }, STONE);
+                    }
+                }
+            }
+        }
+    }
+
+    impl VoxelWorld for TestWorld {
+        fn get_chunk(&self, pos: &ChunkPos) -> Option<&Chunk> {
+            return self.chunks.get(pos);
+        }
+        fn get_chunk_mut(&mut self, pos: &ChunkPos) -> Option<&mut Chunk> {
+            return self.chunks.get_mut(pos);
+        }
+    }
+
+    //A player sized body whose position is at its feet
+    fn test_body() -> Body {
+        return Body::new(Aabb::new([-0.3, 0.0, -0.3], [0.3, 1.8, 0.3]));
+    }
+
+    fn standing_body(world: &TestWorld, x: f64) -> (Body, ObjectPos) {
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x,
+            y: FLOOR_Y as f64,
+            z: 8.0,
+        };
+        body.update(&mut pos, world, DT);
+        assert!(body.on_ground);
+        return (body, pos);
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn clip_axis_stops_at_boxes_in_the_way() {
+        let moving = Aabb::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        let wall = Aabb::new([3.0, 0.0, 0.0], [4.0, 1.0, 1.0]);
+        assert_near(clip_axis(&moving, &[wall], 0, 5.0), 2.0);
+        //moving away from the box or past it on another axis isn't blocked
+        assert_near(clip_axis(&moving, &[wall], 0, -5.0), -5.0);
+        assert_near(clip_axis(&moving, &[wall], 1, 5.0), 5.0);
+        //A box that is already touched is never moved into
+        let touching = Aabb::new([1.0, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        assert_near(clip_axis(&moving, &[touching], 0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn sweep_stops_at_a_wall() {
+        let mut world = TestWorld::with_floor();
+        world.place_ledge(10, 3);
+        let aabb = test_body().get_aabb(&ObjectPos {
+            x: 8.0,
+            y: FLOOR_Y as f64,
+            z: 8.0,
+        });
+        let moved = sweep(&world, &aabb, [5.0, 0.0, 1.0]);
+        assert_near(moved[0], 10.0 - 8.3);
+        assert!(moved[0] < 10.0 - 8.3);
+        //the other axes keep moving along the wall
+        assert_near(moved[2], 1.0);
+        assert_near(sweep(&world, &aabb, [-5.0, 0.0, 0.0])[0], -5.0);
+    }
+
+    #[test]
+    fn falling_bodies_land_on_the_ground() {
+        let world = TestWorld::with_floor();
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x: 8.0,
+            y: FLOOR_Y as f64 + 3.0,
+            z: 8.0,
+        };
+        body.update(&mut pos, &world, DT);
+        assert!(!body.on_ground);
+        for _ in 0..40 {
+            body.update(&mut pos, &world, DT);
+        }
+        assert!(body.on_ground);
+        assert_near(pos.y, FLOOR_Y as f64);
+        assert!(pos.y > FLOOR_Y as f64);
+        assert_eq!(body.velocity[1], 0.0);
+    }
+
+    #[test]
+    fn bodies_only_jump_while_on_the_ground() {
+        let world = TestWorld::with_floor();
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x: 8.0,
+            y: FLOOR_Y as f64 + 3.0,
+            z: 8.0,
+        };
+        body.update(&mut pos, &world, DT);
+        let velocity = body.velocity[1];
+        body.jump();
+        assert_eq!(body.velocity[1], velocity);
+
+        let (mut body, mut pos) = standing_body(&world, 8.0);
+        body.jump();
+        assert_eq!(body.velocity[1], JUMP_VELOCITY);
+        assert!(!body.on_ground);
+        body.update(&mut pos, &world, DT);
+        assert!(pos.y > FLOOR_Y as f64);
+        //A second jump in the air does nothing
+        let velocity = body.velocity[1];
+        body.jump();
+        assert_eq!(body.velocity[1], velocity);
+    }
+
+    #[test]
+    fn falling_stops_accelerating_at_terminal_velocity() {
+        let world = TestWorld::new();
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x: 8.0,
+            y: get_world_config().get_max_y() as f64 - 2.0,
+            z: 8.0,
+        };
+        for _ in 0..60 {
+            body.update(&mut pos, &world, DT);
+        }
+        assert!(pos.y > 0.0);
+        assert_eq!(body.velocity[1], -TERMINAL_VELOCITY);
+    }
+
+    #[test]
+    fn bodies_step_onto_one_block_ledges() {
+        let mut world = TestWorld::with_floor();
+        world.place_ledge(8, 1);
+        let (mut body, mut pos) = standing_body(&world, 6.5);
+        for _ in 0..20 {
+            body.velocity[0] = 4.0;
+            body.update(&mut pos, &world, DT);
+        }
+        assert!(pos.x > 9.0);
+        assert_near(pos.y, FLOOR_Y as f64 + 1.0);
+        assert!(body.on_ground);
+    }
+
+    #[test]
+    fn bodies_dont_step_onto_two_block_ledges() {
+        let mut world = TestWorld::with_floor();
+        world.place_ledge(8, 2);
+        let (mut body, mut pos) = standing_body(&world, 6.5);
+        for _ in 0..20 {
+            body.velocity[0] = 4.0;
+            body.update(&mut pos, &world, DT);
+        }
+        assert_near(pos.x, 8.0 - 0.3);
+        assert_near(pos.y, FLOOR_Y as f64);
+        assert_eq!(body.step_offset, 0.0);
+    }
+
+    #[test]
+    fn the_drawn_height_follows_steps_smoothly() {
+        let mut world = TestWorld::with_floor();
+        world.place_ledge(8, 1);
+        let (mut body, mut pos) = standing_body(&world, 6.5);
+        let mut drawn_y = pos.y + body.step_offset;
+        let mut stepped = false;
+        for _ in 0..20 {
+            body.velocity[0] = 4.0;
+            body.update(&mut pos, &world, DT);
+            let y = pos.y + body.step_offset;
+            assert!(y - drawn_y <= STEP_SMOOTHING * DT + 1e-5);
+            stepped |= body.step_offset < 0.0;
+            drawn_y = y;
+        }
+        assert!(stepped);
+        assert_eq!(body.step_offset, 0.0);
+        assert_near(drawn_y, FLOOR_Y as f64 + 1.0);
+    }
+
+    #[test]
+    fn bodies_inside_a_slab_are_lifted_onto_it() {
+        let mut world = TestWorld::with_floor();
+        world.set_block(
+            &GlobalBlockPos {
+                x: 8,
+                y: FLOOR_Y,
+                z: 8,
+            },
+            STONE_SLAB,
+        );
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x: 8.5,
+            y: FLOOR_Y as f64 + 0.25,
+            z: 8.5,
+        };
+        body.update(&mut pos, &world, DT);
+        assert_near(pos.y, FLOOR_Y as f64 + 0.5);
+        assert_eq!((pos.x, pos.z), (8.5, 8.5));
+        assert!(body.on_ground);
+        //the drawn height follows the lift like it follows A step
+        assert!(body.step_offset < 0.0);
+        body.update(&mut pos, &world, DT);
+        assert_near(pos.y, FLOOR_Y as f64 + 0.5);
+    }
+
+    #[test]
+    fn bodies_inside_a_wall_are_pushed_out_the_short_way() {
+        let mut world = TestWorld::with_floor();
+        world.place_ledge(8, 3);
+        let mut body = test_body();
+        let mut pos = ObjectPos {
+            x: 8.2,
+            y: FLOOR_Y as f64,
+            z: 8.0,
+        };
+        body.update(&mut pos, &world, DT);
+        assert_near(pos.x, 8.0 - 0.3);
+        assert!(pos.x < 8.0 - 0.3);
+        assert_eq!(pos.y, FLOOR_Y as f64);
+    }
+}
@@ -1,21 +1,57 @@
-use crate::world::small_world::SmallWorld;
+use crate::algorithms::raycast::{raycast_blocks, RaycastHit};
+use crate::blocks::block::{
+    BlockId, AIR, DIRT, FENCE, GLASS_PANE, GRASS, LEAF, SAND, STONE, STONE_SLAB, STONE_STAIRS,
+    TALL_GRASS,
+};
+use crate::blocks::block_registry::get_block_registry;
+use crate::movement::{accelerate, MovementMode};
+use crate::physics::{Aabb, Body};
+use crate::world::voxel_world::VoxelWorld;
 use nalgebra::{Matrix3, Vector3};
 use std::f32::consts::PI;
-use vox_core::constants::COLORS;
-use vox_core::positions::{ChunkPos, ObjectPos};
+use vox_core::positions::{ChunkPos, GlobalBlockPos, ObjectPos, ToChunkPos};
 use vox_core::utils::{get_rotation_matrix_y, get_rotation_matrix_z};
 use winit::event::VirtualKeyCode;
+use winit_window_control::input::button::ButtonState;
 use winit_window_control::input::input::Input;
 
+//blocks selected with the number keys 1 to 9 and 0
+const HOTBAR: [(VirtualKeyCode, BlockId); 10] = [
+    (VirtualKeyCode::Key1, STONE),
+    (VirtualKeyCode::Key2, DIRT),
+    (VirtualKeyCode::Key3, GRASS),
+    (VirtualKeyCode::Key4, SAND),
+    (VirtualKeyCode::Key5, LEAF),
+    (VirtualKeyCode::Key6, STONE_SLAB),
+    (VirtualKeyCode::Key7, STONE_STAIRS),
+    (VirtualKeyCode::Key8, GLASS_PANE),
+    (VirtualKeyCode::Key9, TALL_GRASS),
+    (VirtualKeyCode::Key0, FENCE),
+];
+//switches to the next movement mode
+const MOVEMENT_MODE_KEY: VirtualKeyCode = VirtualKeyCode::F;
+
+//the position of the player is the camera, the collision box hangs below it
+const PLAYER_WIDTH: f64 = 0.6;
+const PLAYER_HEIGHT: f64 = 1.8;
+const EYE_HEIGHT: f64 = 1.62;
 pub struct Player {
     pub position: ObjectPos,
     pub direction: Vector3<f32>,
     pub up: [f32; 3],
-    pub speed: f32,
     pub camera_speed: f32,
     pub render_distance: f32,
     pub generated_chunks_for: ChunkPos,
-    pub gravity: f32,
+    pub body: Body,
+    movement_mode: MovementMode,
+    //the direction the player wants to move in, with A length of at most 1
+    wish_direction: [f64; 3],
+    //whether the movement mode key was held last frame, so holding it switches only once
+    mode_key_held: bool,
+    //how far away blocks can be broken and placed
+    pub reach: f64,
+    //block placed with the right mouse button
+    pub selected_block: BlockId,
 }
 
 impl Player {
@@ -28,7 +64,6 @@ impl Player {
             },
             direction: Vector3::new(0f32, 0.0f32, 1.0f32),
             up: [0f32, 1.0f32, 0f32],
-            speed: 100f32,
             camera_speed: 0.5f32,
             render_distance: 5000f32,
             generated_chunks_for: ChunkPos {
@@ -36,39 +71,58 @@ impl Player {
                 y: i32::max_value(),
                 z: i32::max_value(),
             },
-            gravity: 0.0,
+            body: Body::new(Aabb::new(
+                [-PLAYER_WIDTH / 2.0, -EYE_HEIGHT, -PLAYER_WIDTH / 2.0],
+                [
+                    PLAYER_WIDTH / 2.0,
+                    PLAYER_HEIGHT - EYE_HEIGHT,
+                    PLAYER_WIDTH / 2.0,
+                ],
+            )),
+            movement_mode: MovementMode::Walk,
+            wish_direction: [0.0; 3],
+            mode_key_held: false,
+            reach: 8.0,
+            selected_block: STONE,
         }
     }
 
-    pub fn handle_input(&mut self, input: &Input, dt: &f32, world: &SmallWorld) {
-        self.change_position(
-            input,
-            VirtualKeyCode::A,
-            1.5f32 * PI,
-            *dt * self.speed,
-            world,
-        );
-        self.change_position(
-            input,
-            VirtualKeyCode::D,
-            0.5f32 * PI,
-            *dt * self.speed,
-            world,
-        );
-        self.change_position(input, VirtualKeyCode::W, 0f32 * PI, *dt * self.speed, world);
-        self.change_position(input, VirtualKeyCode::S, 1f32 * PI, *dt * self.speed, world);
-        if input.key_pressed(VirtualKeyCode::Space) {
-            let diff = *dt * self.speed;
-            if !Player::collides(&self.position.get_diff(0.0, diff, 0.0), world) {
-                self.position.y += diff;
+    pub fn handle_input(&mut self, input: &Input, dt: &f32) {
+        let mut wish = [0f32; 2];
+        for (key, rotation) in [
+            (VirtualKeyCode::A, 1.5f32 * PI),
+            (VirtualKeyCode::D, 0.5f32 * PI),
+            (VirtualKeyCode::W, 0f32 * PI),
+            (VirtualKeyCode::S, 1f32 * PI),
+        ]
+        .iter()
+        {
+            if input.key_pressed(*key) {
+                let direction = self.get_horizontal_direction(*rotation);
+                wish[0] += direction[0];
+                wish[1] += direction[1];
             }
         }
-        if input.key_pressed(VirtualKeyCode::LShift) {
-            let diff = -*dt * self.speed;
-            if !Player::collides(&self.position.get_diff(0.0, diff, 0.0), world) {
-                self.position.y += diff;
+        let length = (wish[0] * wish[0] + wish[1] * wish[1]).sqrt();
+        let scale = if length > 0f32 { 1f32 / length } else { 0f32 };
+        let mut vertical = 0f64;
+        if self.movement_mode.get_settings().vertical_control {
+            if input.key_pressed(VirtualKeyCode::Space) {
+                vertical += 1.0;
+            }
+            if input.key_pressed(VirtualKeyCode::LShift) {
+                vertical -= 1.0;
             }
+        } else if input.key_pressed(VirtualKeyCode::Space) {
+            self.body.jump();
+        }
+        self.wish_direction = [(wish[0] * scale) as f64, vertical, (wish[1] * scale) as f64];
+
+        let mode_key = input.key_pressed(MOVEMENT_MODE_KEY);
+        if mode_key && !self.mode_key_held {
+            self.set_movement_mode(self.movement_mode.next());
         }
+        self.mode_key_held = mode_key;
 
         let mouse_change = input.mouse_change();
         let xdiff = mouse_change[0] * dt * self.camera_speed;
@@ -86,25 +140,61 @@ impl Player {
         }
     }
 
-    pub fn change_position(
-        &mut self,
-        input: &Input,
-        key: VirtualKeyCode,
-        rotation_degree: f32,
-        change: f32,
-        world: &SmallWorld,
-    ) {
-        if input.key_pressed(key) {
-            let move_vec = get_rotation_matrix_y(rotation_degree) * &self.direction;
-            let to_extend =
-                1f32 / (move_vec[0].powf(2f32).abs() + move_vec[2].powf(2f32).abs()).sqrt();
-            let x_change = change * move_vec.x * to_extend;
-            let z_change = change * move_vec.z * to_extend;
-            if !Player::collides(&self.position.get_diff(x_change, 0.0, z_change), world) {
-                self.position.x += x_change;
-                self.position.z += z_change;
+    //left click breaks the targeted block, right click places the selected block against the
+    //face that was hit
+    pub fn handle_block_input(&mut self, input: &Input, world: &mut impl VoxelWorld) {
+        for (key, block) in HOTBAR.iter() {
+            if input.key_pressed(*key) {
+                self.selected_block = *block;
             }
         }
+        if input.mouse_state.get_left_button() == ButtonState::Pressed {
+            if let Some(hit) = self.get_targeted_block(world) {
+                world.set_block(&hit.block, AIR);
+            }
+        }
+        if input.mouse_state.get_right_button() == ButtonState::Pressed {
+            if let Some(hit) = self.get_targeted_block(world) {
+                self.place_block(&hit, world);
+            }
+        }
+    }
+    pub fn get_targeted_block(&self, world: &impl VoxelWorld) -> Option<RaycastHit> {
+        let direction = [
+            self.direction.x as f64,
+            self.direction.y as f64,
+            self.direction.z as f64,
+        ];
+        return raycast_blocks(world, &self.position, direction, self.reach);
+    }
+    //returns whether the block was placed
+    pub fn place_block(&self, hit: &RaycastHit, world: &mut impl VoxelWorld) -> bool {
+        //the ray started inside the block, so there is no face to place against
+        if hit.normal == [0, 0, 0] || self.occupies_block(&hit.previous) {
+            return false;
+        }
+        let replaceable = match world.get_block(&hit.previous) {
+            Some(b) => get_block_registry().get(b).properties.replaceable,
+            None => false,
+        };
+        if !replaceable {
+            return false;
+        }
+        world.set_block(&hit.previous, self.selected_block);
+        return true;
+    }
+    pub fn occupies_block(&self, pos: &GlobalBlockPos) -> bool {
+        let block = Aabb::new(
+            [pos.x as f64, pos.y as f64, pos.z as f64],
+            [pos.x as f64 + 1.0, pos.y as f64 + 1.0, pos.z as f64 + 1.0],
+        );
+        return self.body.get_aabb(&self.position).intersects(&block);
+    }
+    //the looking direction rotated around the y axis and flattened, with A length of 1
+    pub fn get_horizontal_direction(&self, rotation: f32) -> [f32; 2] {
+        let move_vec = get_rotation_matrix_y(rotation) * self.direction;
+        let to_extend = 1f32 / (move_vec[0].powf(2f32).abs() + move_vec[2].powf(2f32).abs()).sqrt();
+        return [move_vec.x * to_extend, move_vec.z * to_extend];
     }
     pub fn change_direction_horizontal(&mut self, mat: &Matrix3<f32>) {
         self.direction = mat * &self.direction;
@@ -132,27 +222,46 @@ impl Player {
         }
     }
 
-    pub fn update(&mut self, _dt: &f32, world: &SmallWorld) {
-        loop {
-            if Player::collides(&self.position, world) {
-                self.position.y += 1.0;
-            } else {
-                return;
-            }
+    pub fn get_movement_mode(&self) -> MovementMode {
+        return self.movement_mode;
+    }
+    pub fn set_movement_mode(&mut self, mode: MovementMode) {
+        let settings = mode.get_settings();
+        self.movement_mode = mode;
+        self.body.falls = settings.falls;
+        self.body.collides = settings.collides;
+        if !settings.falls {
+            self.body.velocity[1] = 0.0;
         }
     }
-    //pub fn get_collision_points() -> [ObjectPos; 8] {}
-    pub fn collides(pos: &ObjectPos, world: &SmallWorld) -> bool {
-        let blockpos = pos.get_block();
-        let faceblock = world.get_block(blockpos);
-        let feetblock = world.get_block(blockpos.get_diff(0, -1, 0));
-        return if (faceblock.is_some() && COLORS[faceblock.unwrap() as usize][3] == 255.0)
-            || (feetblock.is_some() && COLORS[feetblock.unwrap() as usize][3] == 255.0)
-        {
-            true
+
+    pub fn update(&mut self, dt: &f32, world: &impl VoxelWorld) {
+        let dt = *dt as f64;
+        let settings = self.movement_mode.get_settings();
+        //gravity and jumping own the vertical velocity while walking
+        let axes: &[usize] = if settings.vertical_control {
+            &[0, 1, 2]
+        } else {
+            &[0, 2]
+        };
+        let target = [
+            self.wish_direction[0] * settings.speed,
+            self.wish_direction[1] * settings.speed,
+            self.wish_direction[2] * settings.speed,
+        ];
+        let moving = axes.iter().any(|&a| self.wish_direction[a] != 0.0);
+        let rate = if moving {
+            settings.acceleration
         } else {
-            false
+            settings.friction
         };
+        accelerate(&mut self.body.velocity, target, axes, rate, dt);
+        self.body.update(&mut self.position, world, dt);
+    }
+
+    //where the world is seen from, it trails behind steps so they don't jerk the view up
+    pub fn get_camera_pos(&self) -> ObjectPos {
+        return self.position.get_diff(0.0, self.body.step_offset, 0.0);
     }
 
     pub fn get_view_matrix(&self) -> [[f32; 4]; 4] {
@@ -181,22 +290,15 @@ impl Player {
             f[0] * s_norm[1] - f[1] * s_norm[0],
         ];
 
-        let p = [
-            -self.position.x * s_norm[0]
-                - self.position.y * s_norm[1]
-                - self.position.z * s_norm[2],
-            -self.position.x * u[0] - self.position.y * u[1] - self.position.z * u[2],
-            -self.position.x * f[0] - self.position.y * f[1] - self.position.z * f[2],
-        ];
-
+        //there is no translation, the world is drawn relative to the camera
         [
             [s_norm[0], u[0], f[0], 0.0],
             [s_norm[1], u[1], f[1], 0.0],
             [s_norm[2], u[2], f[2], 0.0],
-            [p[0], p[1], p[2], 1.0],
+            [0.0, 0.0, 0.0, 1.0],
         ]
     }
     pub fn chunk_in_view_distance(&self, pos: &ChunkPos) -> bool {
-        self.position.get_chunk().get_distance(pos) < self.render_distance
+        self.position.get_chunk_pos().get_distance(pos) < self.render_distance
     }
 }
@@ -1,25 +1,29 @@
-use crate::blocks::block::{get_blockid, get_blocktype};
-use crate::blocks::block_type::BlockType;
-use crate::world_gen::meta_chunk::MetaChunk;
+use crate::blocks::block::{LEAF, SAND};
+use crate::blocks::block_registry::get_block_registry;
+use crate::world::voxel_world::VoxelWorld;
+use crate::world_gen::position_rng::{PositionRng, TREE_SHAPE_SALT};
 use rand::distributions::{Distribution, Uniform};
 use vox_core::positions::GlobalBlockPos;
 
-pub fn place_tree(pos: &GlobalBlockPos, world: &mut MetaChunk) {
-    let mut rng = rand::thread_rng();
+pub fn place_tree(pos: &GlobalBlockPos, seed: u32, world: &mut impl VoxelWorld) {
+    let registry = get_block_registry();
+    let mut rng = PositionRng::from_block(seed, pos, TREE_SHAPE_SALT);
     let height_range = Uniform::from(8..12);
     let height = height_range.sample(&mut rng);
     for y in 0..height {
         if y < height - 2 {
-            world.set_block(&pos.get_diff(0, y, 0), get_blockid(BlockType::Sand));
+            world.set_block(&pos.get_diff(0, y, 0), SAND);
         }
         if y >= 4 {
             for x in -(height - y - 1)..height - y {
                 for z in -(height - y - 1)..height - y {
-                    let currect_block = world.get_block(&pos.get_diff(x, y, z));
-                    if currect_block.is_some()
-                        && get_blocktype(currect_block.unwrap()) == BlockType::Air
-                    {
-                        world.set_block(&pos.get_diff(x, y, z), get_blockid(BlockType::Leaf));
+                    //blocks outside of the world are unknown, those writes get deferred
+                    let replaceable = match world.get_block(&pos.get_diff(x, y, z)) {
+                        Some(b) => registry.get(b).properties.replaceable,
+                        None => true,
+                    };
+                    if replaceable {
+                        world.set_block(&pos.get_diff(x, y, z), LEAF);
                     }
                 }
             }
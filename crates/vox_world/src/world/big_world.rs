@@ -0,0 +1,37 @@
+use crate::world::meta_chunk_world::{MetaChunkStore, MetaChunkWorld};
+use crate::world_gen::meta_chunk::MetaChunk;
+use std::collections::HashMap;
+use vox_core::positions::MetaChunkPos;
+
+//same as SmallWorld, but metachunks are stored in A hash map so lookups, inserts and removals
+//don't depend on the amount of loaded metachunks
+pub type BigWorld = MetaChunkWorld<HashedMetaChunks>;
+
+#[derive(Default)]
+pub struct HashedMetaChunks {
+    chunks: HashMap<MetaChunkPos, MetaChunk>,
+}
+
+impl MetaChunkStore for HashedMetaChunks {
+    fn get(&self, pos: &MetaChunkPos) -> Option<&MetaChunk> {
+        return self.chunks.get(pos);
+    }
+    fn get_mut(&mut self, pos: &MetaChunkPos) -> Option<&mut MetaChunk> {
+        return self.chunks.get_mut(pos);
+    }
+    fn insert(&mut self, pos: MetaChunkPos, chunk: MetaChunk) {
+        self.chunks.insert(pos, chunk);
+    }
+    fn remove(&mut self, pos: &MetaChunkPos) -> Option<MetaChunk> {
+        return self.chunks.remove(pos);
+    }
+    fn count(&self) -> usize {
+        return self.chunks.len();
+    }
+    fn iter(&self) -> Box<dyn Iterator<Item = (&MetaChunkPos, &MetaChunk)> + '_> {
+        return Box::new(self.chunks.iter());
+    }
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (&MetaChunkPos, &mut MetaChunk)> + '_> {
+        return Box::new(self.chunks.iter_mut());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::blocks::block_state::BlockState;
+use crate::player::Player;
+use crate::world::voxel_world::VoxelWorld;
+use crate::world_gen::chunk::Chunk;
+use crate::world_gen::deferred_writes::DeferredWrite;
+use crate::world_gen::meta_chunk::MetaChunk;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use vox_core::positions::{ChunkPos, GlobalBlockPos, MetaChunkPos, ToBlockPos, ToChunkPos};
+use vox_core::world_config::get_world_config;
+
+//how A world keeps its loaded metachunks, see SmallWorld and BigWorld
+pub trait MetaChunkStore: Default {
+    fn get(&self, pos: &MetaChunkPos) -> Option<&MetaChunk>;
+    fn get_mut(&mut self, pos: &MetaChunkPos) -> Option<&mut MetaChunk>;
+    fn insert(&mut self, pos: MetaChunkPos, chunk: MetaChunk);
+    fn remove(&mut self, pos: &MetaChunkPos) -> Option<MetaChunk>;
+    fn count(&self) -> usize;
+    fn iter(&self) -> Box<dyn Iterator<Item = (&MetaChunkPos, &MetaChunk)> + '_>;
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (&MetaChunkPos, &mut MetaChunk)> + '_>;
+}
+
+//the loaded part of A world, everything except storing the metachunks is the same for every store
+pub struct MetaChunkWorld<S: MetaChunkStore> {
+    chunks: S,
+    pub loading_chunks: HashSet<MetaChunkPos>,
+    //structure writes into metachunks that aren't loaded, applied once the metachunk is added. They
+    //are only written to disk by take_pending_writes
+    pending_writes: HashMap<MetaChunkPos, Vec<DeferredWrite>>,
+    //chunks whose blocks, or blocks bordering them, changed since the last take_changed_chunks
+    changed_chunks: HashSet<ChunkPos>,
+    pub world_seed: u32,
+    pub time: f64,
+    //the time the world had when it was loaded
+    loaded_time: f64,
+    start_time: Instant,
+}
+
+impl<S: MetaChunkStore> MetaChunkWorld<S> {
+    pub fn new(seed: u32, time: f64) -> MetaChunkWorld<S> {
+        MetaChunkWorld {
+            chunks: S::default(),
+            loading_chunks: HashSet::new(),
+            pending_writes: HashMap::new(),
+            changed_chunks: HashSet::new(),
+            world_seed: seed,
+            time,
+            loaded_time: time,
+            start_time: Instant::now(),
+        }
+    }
+    pub fn count_chunks(&self) -> i32 {
+        return self.chunks.count() as i32;
+    }
+
+    pub fn get_meta_chunk(&self, pos: &MetaChunkPos) -> Option<&MetaChunk> {
+        return self.chunks.get(pos);
+    }
+    pub fn get_meta_chunk_mut(&mut self, pos: &MetaChunkPos) -> Option<&mut MetaChunk> {
+        return self.chunks.get_mut(pos);
+    }
+
+    pub fn add_chunk(&mut self, pos: MetaChunkPos, mut chunk: MetaChunk) {
+        self.loading_chunks.remove(&pos);
+        if let Some(writes) = self.pending_writes.remove(&pos) {
+            chunk.apply_deferred_writes(&writes);
+            chunk.set_modified();
+        }
+        self.route_outgoing_writes(&mut chunk);
+        self.chunks.insert(pos, chunk);
+    }
+    //structure writes into other metachunks are applied if those are loaded, otherwise they are
+    //kept until they get loaded
+    fn route_outgoing_writes(&mut self, chunk: &mut MetaChunk) {
+        for write in chunk.take_outgoing_writes() {
+            let target_pos = write.get_meta_chunk_pos();
+            match self.chunks.get_mut(&target_pos) {
+                Some(target) => {
+                    target.apply_deferred_writes(&[write]);
+                    target.set_modified();
+                    self.changed_chunks.extend(write.pos.get_affected_chunks());
+                }
+                None => self
+                    .pending_writes
+                    .entry(target_pos)
+                    .or_default()
+                    .push(write),
+            }
+        }
+    }
+    //the structure writes into metachunks that aren't loaded, so they can be written to disk. The
+    //writes of metachunks which are being loaded are kept as well, the load may already have read
+    //the writes on disk
+    pub fn take_pending_writes(&mut self) -> Vec<DeferredWrite> {
+        let mut taken = Vec::new();
+        let loading_chunks = &self.loading_chunks;
+        self.pending_writes.retain(|pos, writes| {
+            taken.extend(writes.iter().copied());
+            loading_chunks.contains(pos)
+        });
+        return taken;
+    }
+
+    pub fn remove_chunk(&mut self, pos: &MetaChunkPos) -> Option<MetaChunk> {
+        return self.chunks.remove(pos);
+    }
+    pub fn take_changed_chunks(&mut self) -> HashSet<ChunkPos> {
+        return std::mem::take(&mut self.changed_chunks);
+    }
+
+    //unloads metachunks too far away from the player, the modified ones are returned so they
+    //can be saved
+    pub fn filter_chunks(&mut self, player: &Player) -> Vec<MetaChunk> {
+        let removed: Vec<MetaChunkPos> = self
+            .chunks
+            .iter()
+            .map(|(pos, _)| *pos)
+            .filter(|pos| !MetaChunk::retain_meta_chunk(player, *pos))
+            .collect();
+        let mut to_save = Vec::new();
+        for pos in removed {
+            let mut chunk = self.chunks.remove(&pos).unwrap();
+            if chunk.is_modified() {
+                chunk.mark_saved();
+                to_save.push(chunk);
+            }
+        }
+        return to_save;
+    }
+    //copies of every modified metachunk, which count as saved from now on
+    pub fn take_modified_meta_chunks(&mut self) -> Vec<MetaChunk> {
+        let mut to_save = Vec::new();
+        for (_, chunk) in self.chunks.iter_mut() {
+            if chunk.is_modified() {
+                chunk.mark_saved();
+                to_save.push(chunk.clone());
+            }
+        }
+        return to_save;
+    }
+
+    pub fn update(&mut self) {
+        self.time = self.loaded_time + self.start_time.elapsed().as_secs_f64();
+    }
+    pub fn get_memory_usage(&self) -> usize {
+        return self.chunks.iter().map(|(_, c)| c.get_memory_usage()).sum();
+    }
+    pub fn get_all_chunks(&self) -> impl Iterator<Item = (&MetaChunkPos, &MetaChunk)> {
+        return self.chunks.iter();
+    }
+    pub fn chunk_exists_or_generating(&self, pos: &MetaChunkPos) -> bool {
+        return self.chunks.get(pos).is_some() || self.loading_chunks.contains(pos);
+    }
+}
+
+impl<S: MetaChunkStore> VoxelWorld for MetaChunkWorld<S> {
+    fn get_chunk(&self, pos: &ChunkPos) -> Option<&Chunk> {
+        if !get_world_config().contains_chunk_y(pos.y) {
+            return None;
+        }
+        return match self.get_meta_chunk(&pos.get_meta_chunk_pos()) {
+            Some(chunk) => chunk.get_local_chunk(&pos.get_local_chunk_pos()),
+            None => None,
+        };
+    }
+    fn get_chunk_mut(&mut self, pos: &ChunkPos) -> Option<&mut Chunk> {
+        if !get_world_config().contains_chunk_y(pos.y) {
+            return None;
+        }
+        return match self.get_meta_chunk_mut(&pos.get_meta_chunk_pos()) {
+            Some(chunk) => chunk.get_local_chunk_mut(&pos.get_local_chunk_pos()),
+            None => None,
+        };
+    }
+    fn set_block_state(&mut self, pos: &GlobalBlockPos, state: BlockState) {
+        let local_pos = pos.get_local_pos();
+        match self.get_chunk_mut(&pos.get_chunk_pos()) {
+            Some(c) => {
+                if c.get_block_state(&local_pos) == Some(state) {
+                    return;
+                }
+                c.set_block_state(state, &local_pos)
+            }
+            None => return,
+        };
+        if let Some(meta_chunk) = self.get_meta_chunk_mut(&pos.get_meta_chunk_pos()) {
+            meta_chunk.set_modified();
+        }
+        //faces of the neighboring chunks which touch the changed block can appear or disappear
+        self.changed_chunks.extend(pos.get_affected_chunks());
+    }
+}
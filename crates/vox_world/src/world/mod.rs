@@ -1,2 +1,6 @@
 pub mod big_world;
+pub mod meta_chunk_world;
 pub mod small_world;
+pub mod voxel_world;
+pub mod world_dir;
+pub mod world_info;
@@ -0,0 +1,44 @@
+use crate::blocks::block::BlockId;
+use crate::blocks::block_state::BlockState;
+use crate::world_gen::chunk::Chunk;
+use vox_core::positions::{ChunkPos, GlobalBlockPos, ToBlockPos, ToChunkPos};
+use vox_core::world_config::get_world_config;
+
+//anything that stores chunks and can be read and edited block by block, positions are always
+//global so the same code works on A single metachunk and on A whole world
+pub trait VoxelWorld {
+    fn get_chunk(&self, pos: &ChunkPos) -> Option<&Chunk>;
+    fn get_chunk_mut(&mut self, pos: &ChunkPos) -> Option<&mut Chunk>;
+
+    fn get_block_state(&self, pos: &GlobalBlockPos) -> Option<BlockState> {
+        return match self.get_chunk(&pos.get_chunk_pos()) {
+            Some(c) => c.get_block_state(&pos.get_local_pos()),
+            None => None,
+        };
+    }
+    //does nothing when the position isn't loaded
+    fn set_block_state(&mut self, pos: &GlobalBlockPos, state: BlockState) {
+        if let Some(c) = self.get_chunk_mut(&pos.get_chunk_pos()) {
+            c.set_block_state(state, &pos.get_local_pos());
+        }
+    }
+    fn get_block(&self, pos: &GlobalBlockPos) -> Option<BlockId> {
+        return self.get_block_state(pos).map(|state| state.id);
+    }
+    fn set_block(&mut self, pos: &GlobalBlockPos, block: BlockId) {
+        self.set_block_state(pos, BlockState::new(block));
+    }
+
+    //lowest block y that can exist in the world
+    fn get_min_y(&self) -> i32 {
+        return get_world_config().get_min_y();
+    }
+    //block y above the highest block that can exist in the world
+    fn get_max_y(&self) -> i32 {
+        return get_world_config().get_max_y();
+    }
+    //whether the block is inside A loaded chunk
+    fn contains(&self, pos: &GlobalBlockPos) -> bool {
+        return self.get_chunk(&pos.get_chunk_pos()).is_some();
+    }
+}
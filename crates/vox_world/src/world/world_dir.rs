@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const DEFAULT_WORLD_DIR: &str = "world";
+const LEVEL_FILENAME: &str = "level";
+const REGION_DIR: &str = "region";
+const DEFERRED_WRITES_DIR: &str = "deferred";
+const QUARANTINE_DIR: &str = "corrupt";
+
+//everything belonging to one world is stored in this directory, it is set once at startup
+static WORLD_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+//fails with the given directory if A directory was already set
+pub fn set_world_dir(dir: PathBuf) -> Result<(), PathBuf> {
+    WORLD_DIR.set(dir)
+}
+
+pub fn get_world_dir() -> &'static Path {
+    WORLD_DIR.get_or_init(|| PathBuf::from(DEFAULT_WORLD_DIR))
+}
+
+//seed, config and other metadata of the world
+pub fn get_level_path() -> PathBuf {
+    return get_world_dir().join(LEVEL_FILENAME);
+}
+
+//metachunks, grouped into region files
+pub fn get_region_dir() -> PathBuf {
+    return get_world_dir().join(REGION_DIR);
+}
+
+//structure writes waiting for their metachunk to be loaded
+pub fn get_deferred_writes_dir() -> PathBuf {
+    return get_world_dir().join(DEFERRED_WRITES_DIR);
+}
+
+//damaged files and metachunks are moved here, it is only created once something is damaged
+pub fn get_quarantine_dir() -> PathBuf {
+    return get_world_dir().join(QUARANTINE_DIR);
+}
+
+pub fn create_world_dir() -> std::io::Result<()> {
+    std::fs::create_dir_all(get_region_dir())?;
+    std::fs::create_dir_all(get_deferred_writes_dir())?;
+    return Ok(());
+}
@@ -0,0 +1,240 @@
+use crate::world::world_dir::{create_world_dir, get_level_path, get_world_dir};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use vox_core::world_config::{WorldConfig, WorldConfigOverrides};
+use vox_io::io::error::IoError;
+use vox_io::io::file_reader::read_save_file;
+use vox_io::io::file_writer::write_to_file;
+use vox_io::io::format::SaveFormat;
+
+//the metadata of A world, stored in the level file of the world directory
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct WorldInfo {
+    pub seed: u32,
+    //what the world is stored with, it is installed with set_world_config once the world is
+    //loaded and only read through get_world_config from then on
+    pub config: WorldConfig,
+    //seconds the world has been running
+    pub time: f64,
+    //where A player enters the world
+    pub spawn: [f64; 3],
+}
+
+#[derive(Debug)]
+pub enum WorldInfoError {
+    //dimensions given on the command line differ from the ones the world was created with
+    IncompatibleConfig {
+        stored: WorldConfig,
+        requested: WorldConfig,
+    },
+    //A new world would have no chunks between its lowest and highest chunk y
+    EmptyHeight {
+        min_chunk_y: i32,
+        max_chunk_y: i32,
+    },
+    //the world was saved by A newer version of the game
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    //the world directory couldn't be created
+    CreateDir(String),
+    //the level file couldn't be read or written, A damaged level file is not replaced because
+    //the seed of the world is stored in it
+    Level(IoError),
+}
+
+impl fmt::Display for WorldInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorldInfoError::IncompatibleConfig { stored, requested } => write!(
+                f,
+                "the world was created with chunk size {}, metachunk size {} and chunk y {}..{}, \
+                 but chunk size {}, metachunk size {} and chunk y {}..{} were requested",
+                stored.chunk_size,
+                stored.meta_chunk_size,
+                stored.min_chunk_y,
+                stored.max_chunk_y,
+                requested.chunk_size,
+                requested.meta_chunk_size,
+                requested.min_chunk_y,
+                requested.max_chunk_y
+            ),
+            WorldInfoError::EmptyHeight {
+                min_chunk_y,
+                max_chunk_y,
+            } => write!(
+                f,
+                "the highest chunk y {} has to be above the lowest chunk y {}",
+                max_chunk_y, min_chunk_y
+            ),
+            WorldInfoError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "the world has version {}, but only versions up to {} are supported",
+                found, supported
+            ),
+            WorldInfoError::CreateDir(error) => write!(
+                f,
+                "couldn't create the world directory {}: {}",
+                get_world_dir().display(),
+                error
+            ),
+            WorldInfoError::Level(error) => write!(
+                f,
+                "couldn't use the level file {}: {}",
+                get_level_path().display(),
+                error
+            ),
+        }
+    }
+}
+
+impl SaveFormat for WorldInfo {
+    const FORMAT_VERSION: u32 = 1;
+}
+
+impl WorldInfo {
+    //an existing world keeps its seed and its config, the requested seed is only used for A new
+    //world. Overridden ranges are always used, they don't change the saved chunks, but overridden
+    //dimensions have to match the stored ones
+    pub fn load_or_create(
+        requested_seed: Option<u32>,
+        overrides: WorldConfigOverrides,
+    ) -> Result<WorldInfo, WorldInfoError> {
+        if let Err(e) = create_world_dir() {
+            return Err(WorldInfoError::CreateDir(e.to_string()));
+        }
+        let stored = match read_save_file::<WorldInfo>(get_level_path()) {
+            Ok(stored) => stored,
+            Err(IoError::UnsupportedVersion { found, supported }) => {
+                return Err(WorldInfoError::UnsupportedVersion { found, supported })
+            }
+            Err(e) => return Err(WorldInfoError::Level(e)),
+        };
+        if let Some(stored) = stored {
+            return stored.reopen(requested_seed, overrides);
+        }
+        let config = overrides.apply_to(WorldConfig::new());
+        if config.max_chunk_y <= config.min_chunk_y {
+            return Err(WorldInfoError::EmptyHeight {
+                min_chunk_y: config.min_chunk_y,
+                max_chunk_y: config.max_chunk_y,
+            });
+        }
+        let seed = requested_seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as u32
+        });
+        let info = WorldInfo {
+            seed,
+            config,
+            time: 0.0,
+            //above the highest block, the player falls down once the terrain is loaded
+            spawn: [0.5, config.get_max_y() as f64, 0.5],
+        };
+        if let Err(e) = info.save() {
+            return Err(WorldInfoError::Level(e));
+        }
+        return Ok(info);
+    }
+    //the stored world with the overrides applied
+    fn reopen(
+        self,
+        requested_seed: Option<u32>,
+        overrides: WorldConfigOverrides,
+    ) -> Result<WorldInfo, WorldInfoError> {
+        let config = overrides.apply_to(self.config);
+        if !self.config.is_compatible(&config) {
+            return Err(WorldInfoError::IncompatibleConfig {
+                stored: self.config,
+                requested: config,
+            });
+        }
+        if let Some(seed) = requested_seed {
+            if seed != self.seed {
+                println!(
+                    "ignoring seed {}, the existing world was created with seed {}",
+                    seed, self.seed
+                );
+            }
+        }
+        return Ok(WorldInfo { config, ..self });
+    }
+    pub fn save(&self) -> Result<(), IoError> {
+        return write_to_file(get_level_path(), self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vox_io::io::format::decode;
+
+    fn get_stored() -> WorldInfo {
+        WorldInfo {
+            seed: 7,
+            config: WorldConfig {
+                chunk_size: 16,
+                meta_chunk_size: 4,
+                min_chunk_y: -2,
+                max_chunk_y: 6,
+                ..WorldConfig::new()
+            },
+            time: 12.5,
+            spawn: [0.5, 96.0, 0.5],
+        }
+    }
+
+    #[test]
+    fn reopening_without_overrides_keeps_the_stored_config() {
+        let stored = get_stored();
+        let info = stored
+            .reopen(None, WorldConfigOverrides::default())
+            .unwrap();
+        assert_eq!(info, stored);
+    }
+
+    #[test]
+    fn reopening_uses_overridden_ranges() {
+        let overrides = WorldConfigOverrides {
+            meta_chunk_gen_range: Some(5),
+            ..WorldConfigOverrides::default()
+        };
+        let info = get_stored().reopen(Some(3), overrides).unwrap();
+        assert_eq!(info.config.meta_chunk_gen_range, 5);
+        assert_eq!(info.seed, 7);
+    }
+
+    #[test]
+    fn reopening_with_other_dimensions_fails() {
+        let overrides = WorldConfigOverrides {
+            chunk_size: Some(32),
+            ..WorldConfigOverrides::default()
+        };
+        match get_stored().reopen(None, overrides) {
+            Err(WorldInfoError::IncompatibleConfig { stored, requested }) => {
+                assert_eq!(stored.chunk_size, 16);
+                assert_eq!(requested.chunk_size, 32);
+            }
+            other => panic!("expected an incompatible config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn version_1_levels_decode() {
+        let bytes = include_bytes!("../../tests/fixtures/level_v1.bin");
+        assert_eq!(decode::<WorldInfo>(bytes).unwrap(), get_stored());
+    }
+
+    //A damaged level has to fail instead of loading another world
+    #[test]
+    fn damaged_levels_are_corrupt() {
+        let mut bytes = include_bytes!("../../tests/fixtures/level_v1.bin").to_vec();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        assert!(decode::<WorldInfo>(&bytes).unwrap_err().is_corrupt());
+    }
+}
@@ -1,10 +1,11 @@
-use crate::blocks::block::{get_blockid, get_blocktype};
-use crate::blocks::block_type::BlockType;
-use crate::world_gen::chunk::{Chunk, ChunkData};
+use crate::blocks::block::{AIR, GRASS, STONE, WATER};
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
+use crate::world_gen::chunk::Chunk;
 use noise::{Fbm, MultiFractal, NoiseFn, Seedable};
 use std::cmp::max;
-use vox_core::constants::{CHUNKSIZE, METACHUNKSIZE};
 use vox_core::positions::{ChunkPos, LocalBlockPos};
+use vox_core::world_config::{get_chunk_size, get_world_config};
 
 pub struct ChunkGenerator {
     pub noise: Fbm,
@@ -23,7 +24,7 @@ impl ChunkGenerator {
                 .set_seed(seed)
                 .set_octaves(3)
                 .set_persistence(0.6f64),
-            seed: 1,
+            seed,
             functions,
         }
     }
@@ -36,42 +37,36 @@ impl ChunkGenerator {
     }
 }
 pub fn generate_empty_chunk() -> Chunk {
-    let empty = [get_blockid(BlockType::Air); CHUNKSIZE * CHUNKSIZE * CHUNKSIZE];
-    /*let mut arr: Vec<Block> = Vec::with_capacity(CHUNKSIZE * CHUNKSIZE * CHUNKSIZE);
-    for x in 0..CHUNKSIZE as i32 {
-        for y in 0..CHUNKSIZE as i32 {
-            for _ in 0..CHUNKSIZE as i32 {
-                arr.push(block::Block::new(BlockType::Air));
-            }
-        }
-    }*/
-    return Chunk::new(ChunkData { d: empty });
+    return Chunk::new(BlockState::new(AIR));
 }
 
 pub fn generate_landmass(chunk_generator: &ChunkGenerator, pos: &ChunkPos, chunk: &mut Chunk) {
-    for x in 0..CHUNKSIZE as i32 {
-        for z in 0..CHUNKSIZE as i32 {
+    for x in 0..get_chunk_size() as i32 {
+        for z in 0..get_chunk_size() as i32 {
             let height = get_xz_heigth(x, z, chunk_generator, pos);
-            let height = height - pos.y * CHUNKSIZE as i32;
+            let height = height - pos.y * get_chunk_size() as i32;
             for y in 0..height {
-                if y >= CHUNKSIZE as i32 {
+                if y >= get_chunk_size() as i32 {
                     continue;
                 }
-                chunk.set_block(get_blockid(BlockType::Stone), &LocalBlockPos { x, y, z });
+                chunk.set_block(STONE, &LocalBlockPos { x, y, z });
             }
         }
     }
 }
 pub fn plant_grass(chunk_generator: &ChunkGenerator, pos: &ChunkPos, chunk: &mut Chunk) {
-    for x in 0..CHUNKSIZE as i32 {
-        for z in 0..CHUNKSIZE as i32 {
+    for x in 0..get_chunk_size() as i32 {
+        for z in 0..get_chunk_size() as i32 {
             let height = get_xz_heigth(x, z, chunk_generator, pos);
-            if height < (pos.y + 1) * CHUNKSIZE as i32 && height >= (pos.y) * CHUNKSIZE as i32 {
-                let y = height - pos.y * CHUNKSIZE as i32;
-                if height > (CHUNKSIZE as f32 * METACHUNKSIZE as f32 * 0.8) as i32 {
+            if height < (pos.y + 1) * get_chunk_size() as i32
+                && height >= (pos.y) * get_chunk_size() as i32
+            {
+                let y = height - pos.y * get_chunk_size() as i32;
+                let config = get_world_config();
+                if height > config.get_min_y() + (config.get_height() as f32 * 0.8) as i32 {
                     continue;
                 }
-                chunk.set_block(get_blockid(BlockType::Grass), &LocalBlockPos { x, y, z });
+                chunk.set_block(GRASS, &LocalBlockPos { x, y, z });
             }
         }
     }
@@ -79,24 +74,32 @@ pub fn plant_grass(chunk_generator: &ChunkGenerator, pos: &ChunkPos, chunk: &mut
 
 fn get_xz_heigth(x: i32, z: i32, chunk_generator: &ChunkGenerator, pos: &ChunkPos) -> i32 {
     let noise = [
-        (x + (pos.x * CHUNKSIZE as i32)) as f64 / (METACHUNKSIZE * CHUNKSIZE) as f64,
-        (z + (pos.z * CHUNKSIZE as i32)) as f64 / (METACHUNKSIZE * CHUNKSIZE) as f64,
+        (x + (pos.x * get_chunk_size() as i32)) as f64
+            / get_world_config().get_meta_chunk_blocks() as f64,
+        (z + (pos.z * get_chunk_size() as i32)) as f64
+            / get_world_config().get_meta_chunk_blocks() as f64,
     ];
-    ((chunk_generator.noise.get(noise) + 1.0) * METACHUNKSIZE as f64 * CHUNKSIZE as f64 / 2.0)
-        as i32
+    //the terrain spans the whole vertical range of the world
+    let config = get_world_config();
+    config.get_min_y()
+        + ((chunk_generator.noise.get(noise) + 1.0) * config.get_height() as f64 / 2.0) as i32
 }
 
 pub fn floodfill_water(_: &ChunkGenerator, pos: &ChunkPos, chunk: &mut Chunk) {
-    for x in 0..CHUNKSIZE as i32 {
-        for z in 0..CHUNKSIZE as i32 {
-            for y in 0..CHUNKSIZE as i32 {
-                let water_level = CHUNKSIZE * METACHUNKSIZE / 3;
-                let global_y = (y as i32 + (pos.y * CHUNKSIZE as i32)) as f64;
+    let registry = get_block_registry();
+    for x in 0..get_chunk_size() as i32 {
+        for z in 0..get_chunk_size() as i32 {
+            for y in 0..get_chunk_size() as i32 {
+                let config = get_world_config();
+                let water_level = config.get_min_y() + config.get_height() as i32 / 3;
+                let global_y = (y + (pos.y * get_chunk_size() as i32)) as f64;
                 if global_y < water_level as f64
-                    && get_blocktype(chunk.get_block(&LocalBlockPos { x, y, z }).unwrap())
-                        == BlockType::Air
+                    && registry
+                        .get(chunk.get_block(&LocalBlockPos { x, y, z }).unwrap())
+                        .properties
+                        .replaceable
                 {
-                    chunk.set_block(get_blockid(BlockType::Water), &LocalBlockPos { x, y, z });
+                    chunk.set_block(WATER, &LocalBlockPos { x, y, z });
                 }
             }
         }
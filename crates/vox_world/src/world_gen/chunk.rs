@@ -1,51 +1,36 @@
-use crate::blocks::block::{get_blocktype, BlockId};
-use crate::blocks::block_type::BlockType;
+use crate::blocks::block::{BlockId, AIR};
+use crate::blocks::block_model::BlockModel;
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
 use crate::world_gen::basic::ChunkGenerator;
-use bytemuck::{Pod, Zeroable};
+use crate::world_gen::packed_array::PackedArray;
 use serde::{Deserialize, Serialize};
-use serde_big_array::big_array;
-use vox_core::constants::CHUNKSIZE;
 use vox_core::positions::{ChunkPos, LocalBlockPos};
+use vox_core::world_config::{get_chunk_size, get_world_config};
 
-big_array! { BigArray;
-CHUNKSIZE * CHUNKSIZE * CHUNKSIZE}
-
-#[repr(C)]
-#[derive(Serialize, Deserialize, Copy, Clone)]
-pub struct ChunkData {
-    #[serde(with = "BigArray")]
-    pub d: [BlockId; CHUNKSIZE * CHUNKSIZE * CHUNKSIZE],
-}
-
-#[derive(Serialize, Deserialize)]
+//every block is stored as an index into the palette, using as few bits as the palette allows.
+//counts holds how many blocks use every palette entry, entries with A count of 0 get reused
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Chunk {
-    pub blocks: ChunkData,
-    pub is_completely_air: bool,
+    blocks: PackedArray,
+    palette: Vec<BlockState>,
+    counts: Vec<u32>,
+    non_air_count: u32,
 }
 
 impl Chunk {
-    pub fn new(blocks: ChunkData) -> Chunk {
+    pub fn new(fill: BlockState) -> Chunk {
+        let volume = get_world_config().get_chunk_volume() as u32;
         Chunk {
-            blocks,
-            is_completely_air: false,
+            blocks: PackedArray::new(volume as usize),
+            palette: vec![fill],
+            counts: vec![volume],
+            non_air_count: if fill.id == AIR { 0 } else { volume },
         }
     }
     pub fn generate(pos: &ChunkPos, seed: u32) -> Chunk {
         let chunk_generator = ChunkGenerator::new(seed);
-        let mut chunk = chunk_generator.full_generation_pass(pos);
-        for x in 0..CHUNKSIZE as i32 {
-            for y in 0..CHUNKSIZE as i32 {
-                for z in 0..CHUNKSIZE as i32 {
-                    if get_blocktype(chunk.get_block(&LocalBlockPos { x, y, z }).unwrap())
-                        != BlockType::Air
-                    {
-                        return chunk;
-                    }
-                }
-            }
-        }
-        chunk.is_completely_air = true;
-        return chunk;
+        return chunk_generator.full_generation_pass(pos);
     }
 
     pub fn update(&mut self, _dt: f32) -> bool {
@@ -53,35 +38,110 @@ impl Chunk {
     }
 
     pub fn set_block(&mut self, block: BlockId, pos: &LocalBlockPos) {
-        if pos.x < 0
-            || pos.x > (CHUNKSIZE - 1) as i32
-            || pos.y < 0
-            || pos.y > (CHUNKSIZE - 1) as i32
-            || pos.z < 0
-            || pos.z > (CHUNKSIZE - 1) as i32
-        {
-            println!("couldn't set block at: {:?}", &pos);
+        self.set_block_state(BlockState::new(block), pos);
+    }
+    pub fn get_block(&self, pos: &LocalBlockPos) -> Option<BlockId> {
+        return self.get_block_state(pos).map(|state| state.id);
+    }
+    pub fn set_block_state(&mut self, state: BlockState, pos: &LocalBlockPos) {
+        let i = match Chunk::get_index(pos) {
+            Some(i) => i,
+            None => {
+                println!("couldn't set block at: {:?}", &pos);
+                return;
+            }
+        };
+        let old_index = self.blocks.get(i);
+        let old_state = self.palette[old_index];
+        if old_state == state {
             return;
         }
-        self.blocks.d[pos.x as usize
-            + pos.y as usize * CHUNKSIZE as usize
-            + pos.z as usize * CHUNKSIZE as usize * CHUNKSIZE as usize] = block;
+        let index = self.get_palette_index(state);
+        //growing the palette can repack the blocks, but never changes the indices
+        self.counts[old_index] -= 1;
+        self.counts[index] += 1;
+        if old_state.id == AIR && state.id != AIR {
+            self.non_air_count += 1;
+        } else if old_state.id != AIR && state.id == AIR {
+            self.non_air_count -= 1;
+        }
+        let volume = self.blocks.len() as u32;
+        if self.counts[index] == volume {
+            self.palette = vec![state];
+            self.counts = vec![volume];
+            self.blocks.clear();
+            return;
+        }
+        self.blocks.set(i, index);
     }
-    pub fn get_block(&self, pos: &LocalBlockPos) -> Option<BlockId> {
-        if pos.x < 0
-            || pos.x >= (CHUNKSIZE) as i32
-            || pos.y < 0
-            || pos.y >= (CHUNKSIZE) as i32
-            || pos.z < 0
-            || pos.z >= (CHUNKSIZE) as i32
-        {
-            println!("couldn't get block at: {:?}", &pos);
+    pub fn get_block_state(&self, pos: &LocalBlockPos) -> Option<BlockState> {
+        return match Chunk::get_index(pos) {
+            Some(i) => Some(self.palette[self.blocks.get(i)]),
+            None => {
+                println!("couldn't get block at: {:?}", &pos);
+                None
+            }
+        };
+    }
+    //A chunk with A single palette entry only contains that block and stores no block data,
+    //A chunk gets collapsed to A single entry as soon as one block fills all of it
+    pub fn is_uniform(&self) -> bool {
+        return self.blocks.get_bits() == 0;
+    }
+    pub fn get_uniform_block(&self) -> Option<BlockState> {
+        if self.is_uniform() {
+            return Some(self.palette[0]);
+        }
+        return None;
+    }
+    pub fn is_completely_air(&self) -> bool {
+        return self.non_air_count == 0;
+    }
+    //whether the chunk is filled with A single full opaque block, so it hides everything behind it
+    pub fn is_completely_opaque(&self) -> bool {
+        return match self.get_uniform_block() {
+            None => false,
+            Some(state) => {
+                let definition = get_block_registry().get(state.id);
+                definition.model == BlockModel::Cube
+                    && definition.properties.occludes
+                    && !definition.transparent
+            }
+        };
+    }
+    pub fn get_non_air_count(&self) -> u32 {
+        return self.non_air_count;
+    }
+    //palette entries with A count of 0 aren't used by any block
+    pub fn get_palette(&self) -> &[BlockState] {
+        return &self.palette;
+    }
+    pub fn get_memory_usage(&self) -> usize {
+        return std::mem::size_of::<Chunk>()
+            + self.blocks.get_memory_usage()
+            + self.palette.capacity() * std::mem::size_of::<BlockState>()
+            + self.counts.capacity() * std::mem::size_of::<u32>();
+    }
+
+    #[inline]
+    fn get_index(pos: &LocalBlockPos) -> Option<usize> {
+        let size = get_chunk_size() as i32;
+        if pos.x < 0 || pos.x >= size || pos.y < 0 || pos.y >= size || pos.z < 0 || pos.z >= size {
             return None;
         }
-        return Some(
-            self.blocks.d[pos.x as usize
-                + pos.y as usize * CHUNKSIZE as usize
-                + pos.z as usize * CHUNKSIZE as usize * CHUNKSIZE as usize],
-        );
+        return Some((pos.x + pos.y * size + pos.z * size * size) as usize);
+    }
+    fn get_palette_index(&mut self, state: BlockState) -> usize {
+        if let Some(i) = self.palette.iter().position(|s| *s == state) {
+            return i;
+        }
+        if let Some(i) = self.counts.iter().position(|count| *count == 0) {
+            self.palette[i] = state;
+            return i;
+        }
+        self.palette.push(state);
+        self.counts.push(0);
+        self.blocks.grow(self.palette.len() - 1);
+        return self.palette.len() - 1;
     }
 }
@@ -1,25 +1,47 @@
+use crate::world_gen::deferred_writes::{clear_deferred_writes, defer_writes, DeferredWrite};
 use crate::world_gen::meta_chunk::MetaChunk;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, SendError, Sender, TryRecvError};
+use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Instant;
 use vox_core::positions::MetaChunkPos;
 
+//requests are handled in the order they are sent, so A metachunk that is saved and then loaded
+//again is always read after it was written
+pub enum ChunkGenRequest {
+    //load or generate the metachunk with the given seed
+    Load(MetaChunkPos, u32),
+    //the deferred writes of the metachunk are removed from disk once it is written, they are part
+    //of the saved metachunk
+    Save(MetaChunk),
+    //structure writes into metachunks that aren't loaded, stored until those get loaded
+    DeferWrites(Vec<DeferredWrite>),
+    //answered once every request sent before it is handled
+    Flush(Sender<()>),
+}
+
 pub struct ChunkGenThread {
-    pub chunk_generator_requester: Sender<(MetaChunkPos, u32)>,
+    pub chunk_generator_requester: Sender<ChunkGenRequest>,
     pub chunk_generator_receiver: Receiver<(MetaChunk, MetaChunkPos)>,
     pub chunk_generator_thread: JoinHandle<()>,
+    //load requests are skipped once set, so quitting doesn't wait for metachunks nobody needs
+    stop_loading: Arc<AtomicBool>,
 }
 
 impl ChunkGenThread {
     pub fn new() -> ChunkGenThread {
         let (gen_chunk_request, gen_chunk_receiver) = mpsc::channel();
         let (gen_chunk_request_done, gen_chunk_receiver_done) = mpsc::channel();
+        let stop_loading = Arc::new(AtomicBool::new(false));
+        let thread_stop_loading = stop_loading.clone();
         let chunk_gen_thread = thread::spawn(move || loop {
-            let message: Result<(MetaChunkPos, u32), TryRecvError> = gen_chunk_receiver.try_recv();
+            let message: Result<ChunkGenRequest, TryRecvError> = gen_chunk_receiver.try_recv();
             match message {
-                Ok((pos, seed)) => {
+                Ok(ChunkGenRequest::Load(_, _)) if thread_stop_loading.load(Ordering::Relaxed) => {}
+                Ok(ChunkGenRequest::Load(pos, seed)) => {
                     let timer = Instant::now();
                     println!("started generation for {:?}", pos);
                     let result = gen_chunk_request_done
@@ -35,28 +57,67 @@ impl ChunkGenThread {
                         }
                     }
                 }
+                Ok(ChunkGenRequest::Save(chunk)) => {
+                    let timer = Instant::now();
+                    match chunk.save_to_disk() {
+                        Err(e) => println!("error while saving {:?}: {}", chunk.pos, e),
+                        Ok(_) => {
+                            clear_deferred_writes(&chunk.pos);
+                            println!(
+                                "saved {:?} in {} sec",
+                                chunk.pos,
+                                timer.elapsed().as_secs_f32()
+                            )
+                        }
+                    }
+                }
+                Ok(ChunkGenRequest::DeferWrites(writes)) => defer_writes(writes),
+                Ok(ChunkGenRequest::Flush(done)) => {
+                    let _ = done.send(());
+                }
                 Err(e) => {
                     if e == TryRecvError::Disconnected {
                         return;
                     }
                 }
             }
-            if message.is_err() {
-            } else {
-            }
         });
         return ChunkGenThread {
             chunk_generator_requester: gen_chunk_request,
             chunk_generator_receiver: gen_chunk_receiver_done,
             chunk_generator_thread: chunk_gen_thread,
+            stop_loading,
         };
     }
-    pub fn request(
+    pub fn request(&self, pos: MetaChunkPos, seed: u32) -> Result<(), SendError<ChunkGenRequest>> {
+        self.chunk_generator_requester
+            .send(ChunkGenRequest::Load(pos, seed))
+    }
+    //the metachunk is written to disk on the thread
+    pub fn save(&self, chunk: MetaChunk) -> Result<(), SendError<ChunkGenRequest>> {
+        self.chunk_generator_requester
+            .send(ChunkGenRequest::Save(chunk))
+    }
+    //the writes are stored on the thread, A metachunk requested after this reads them
+    pub fn defer_writes(
         &self,
-        pos: MetaChunkPos,
-        seed: u32,
-    ) -> Result<(), SendError<(MetaChunkPos, u32)>> {
-        self.chunk_generator_requester.send((pos, seed))
+        writes: Vec<DeferredWrite>,
+    ) -> Result<(), SendError<ChunkGenRequest>> {
+        self.chunk_generator_requester
+            .send(ChunkGenRequest::DeferWrites(writes))
+    }
+    //blocks until every save sent so far is written. Loads that are still waiting are skipped,
+    //this is meant for quitting
+    pub fn finish_saving(&self) {
+        self.stop_loading.store(true, Ordering::Relaxed);
+        let (done, wait) = mpsc::channel();
+        if self
+            .chunk_generator_requester
+            .send(ChunkGenRequest::Flush(done))
+            .is_ok()
+        {
+            let _ = wait.recv();
+        }
     }
     pub fn get(&self) -> Result<(MetaChunk, MetaChunkPos), TryRecvError> {
         self.chunk_generator_receiver.try_recv()
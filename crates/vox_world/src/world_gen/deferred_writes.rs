@@ -0,0 +1,118 @@
+use crate::blocks::block_state::BlockState;
+use crate::world::world_dir::{get_deferred_writes_dir, get_quarantine_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use vox_core::positions::{GlobalBlockPos, MetaChunkPos, ToChunkPos};
+use vox_io::io::file_reader::read_save_file;
+use vox_io::io::file_writer::write_to_file;
+use vox_io::io::format::SaveFormat;
+use vox_io::io::quarantine::quarantine_file;
+
+//A block written by A structure into A metachunk that wasn't loaded at the time. The world keeps
+//them in memory, when it saves or unloads metachunks the chunk generation thread writes them to
+//disk, where they stay until the target metachunk is saved
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct DeferredWrite {
+    pub pos: GlobalBlockPos,
+    pub state: BlockState,
+}
+
+impl DeferredWrite {
+    pub fn get_meta_chunk_pos(&self) -> MetaChunkPos {
+        self.pos.get_meta_chunk_pos()
+    }
+}
+
+//the contents of A deferred writes file
+#[derive(Serialize, Deserialize)]
+struct DeferredWrites(Vec<DeferredWrite>);
+
+impl SaveFormat for DeferredWrites {
+    const FORMAT_VERSION: u32 = 1;
+}
+
+//only called from the chunk generation thread, which also loads and saves the target metachunks
+pub fn defer_writes(writes: Vec<DeferredWrite>) {
+    let mut per_meta_chunk: HashMap<MetaChunkPos, Vec<DeferredWrite>> = HashMap::new();
+    for write in writes {
+        per_meta_chunk
+            .entry(write.get_meta_chunk_pos())
+            .or_default()
+            .push(write);
+    }
+    for (pos, new_writes) in per_meta_chunk {
+        let mut pending = load_deferred_writes(&pos);
+        pending.extend(new_writes);
+        if let Err(e) = write_to_file(get_filename(&pos), &DeferredWrites(pending)) {
+            println!("error while deferring writes to {:?}: {}", pos, e);
+        }
+    }
+}
+
+pub fn load_deferred_writes(pos: &MetaChunkPos) -> Vec<DeferredWrite> {
+    let filename = get_filename(pos);
+    return match read_save_file(&filename) {
+        Ok(writes) => writes.map(|w: DeferredWrites| w.0).unwrap_or_else(Vec::new),
+        //the structures these writes came from are lost, the metachunk itself is still fine
+        Err(e) => {
+            println!("deferred writes of {:?} are unreadable: {}", pos, e);
+            if e.is_corrupt() {
+                if let Err(e) = quarantine_file(&filename, &get_quarantine_dir()) {
+                    println!("couldn't move them to the quarantine: {}", e);
+                }
+            }
+            Vec::new()
+        }
+    };
+}
+
+//called once the writes are part of the saved metachunk
+pub fn clear_deferred_writes(pos: &MetaChunkPos) {
+    let _ = std::fs::remove_file(get_filename(pos));
+}
+
+fn get_filename(pos: &MetaChunkPos) -> PathBuf {
+    get_deferred_writes_dir().join(format!("{}.{}", pos.x, pos.z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::block::{FENCE, STONE, STONE_SLAB};
+    use crate::blocks::block_state::{Axis, Half};
+    use vox_io::io::format::decode;
+
+    //the writes stored in the fixtures
+    fn get_fixture_writes() -> Vec<DeferredWrite> {
+        vec![
+            DeferredWrite {
+                pos: GlobalBlockPos {
+                    x: 40,
+                    y: 71,
+                    z: -3,
+                },
+                state: BlockState::new(STONE),
+            },
+            DeferredWrite {
+                pos: GlobalBlockPos {
+                    x: -1,
+                    y: 0,
+                    z: 300,
+                },
+                state: BlockState::new(STONE_SLAB).with_half(Half::Top),
+            },
+            DeferredWrite {
+                pos: GlobalBlockPos { x: 7, y: 200, z: 7 },
+                state: BlockState::new(FENCE).with_axis(Axis::Z),
+            },
+        ]
+    }
+
+    #[test]
+    fn version_1_deferred_writes_decode() {
+        let bytes = include_bytes!("../../tests/fixtures/deferred_writes_v1.bin");
+        let writes = decode::<DeferredWrites>(bytes).unwrap();
+        assert_eq!(writes.0, get_fixture_writes());
+    }
+}
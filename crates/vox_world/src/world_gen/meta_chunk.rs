@@ -1,74 +1,109 @@
 use crate::algorithms::bfs_world::bfs_world_air;
 
-use crate::blocks::block::{get_blockid, get_blocktype, BlockId};
-use crate::blocks::block_type::BlockType;
+use crate::blocks::block::{GRASS, SAND};
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
 use crate::player::Player;
 use crate::structures::square::place_square;
 use crate::structures::tree::place_tree;
+use crate::world::voxel_world::VoxelWorld;
+use crate::world::world_dir::{get_quarantine_dir, get_region_dir};
 use crate::world_gen::basic::ChunkGenerator;
 use crate::world_gen::chunk::Chunk;
+use crate::world_gen::deferred_writes::{load_deferred_writes, DeferredWrite};
+use crate::world_gen::position_rng::{PositionRng, TREE_PLACEMENT_SALT};
 use rand::distributions::{Distribution, Standard, Uniform};
-use rand::prelude::*;
 use rand_distr::Normal;
 use serde::{Deserialize, Serialize};
 use std::borrow::BorrowMut;
-use vox_core::constants::{CHUNKSIZE, METACHUNKSIZE, METACHUNK_GEN_RANGE};
-use vox_core::positions::{ChunkPos, GlobalBlockPos, LocalChunkPos, MetaChunkPos};
-use vox_core::utils::{to_sign_of, wrap};
-use vox_io::io::file_reader::read_meta_chunk_from_file;
-use vox_io::io::file_writer::write_to_file;
+use vox_core::positions::{
+    ChunkPos, GlobalBlockPos, LocalChunkPos, MetaChunkPos, ToBlockPos, ToChunkPos,
+};
+use vox_core::world_config::{get_meta_chunk_gen_range, get_meta_chunk_size, get_world_config};
+use vox_io::io::error::IoError;
+use vox_io::io::format::SaveFormat;
+use vox_io::io::region::{quarantine_region_entry, read_from_region, write_to_region};
 
-#[derive(Serialize, Deserialize)]
+//A column of chunks from the lowest to the highest chunk y of the world. It is generated, loaded
+//and saved as A whole, chunks are only loaded by their distance from the player along x and z, so
+//memory and generation time grow with the height of the world
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MetaChunk {
     chunks: Vec<Chunk>,
     pub pos: MetaChunkPos,
     pub seed: u32,
+    //writes of structures that reach outside of this metachunk, taken by the world once loaded
+    #[serde(skip)]
+    outgoing_writes: Vec<DeferredWrite>,
+    //edited since it was loaded, generated or last handed over for saving
+    #[serde(skip)]
+    modified: bool,
 }
 
 impl MetaChunk {
     pub fn load_or_gen(pos: MetaChunkPos, seed: u32, force_gen: bool) -> MetaChunk {
         if !force_gen {
-            let loaded = MetaChunk::load_from_disk(&pos);
-            if loaded.is_some() {
-                return loaded.unwrap();
+            match MetaChunk::load_from_disk(&pos) {
+                Ok(Some(mut chunk)) => {
+                    chunk.apply_deferred_writes(&load_deferred_writes(&pos));
+                    return chunk;
+                }
+                Ok(None) => {}
+                //A damaged metachunk is moved out of the way and generated again
+                Err(e) if e.is_corrupt() => {
+                    println!("metachunk {:?} is corrupt: {}", pos, e);
+                    match quarantine_region_entry(
+                        &get_region_dir(),
+                        pos.x,
+                        pos.z,
+                        &get_quarantine_dir(),
+                    ) {
+                        Ok(path) => println!("moved it to {}", path.display()),
+                        Err(e) => println!("couldn't move it to the quarantine: {}", e),
+                    }
+                }
+                Err(e) => println!("couldn't load metachunk {:?}, generating it: {}", pos, e),
             }
         }
         let chunk_generator = ChunkGenerator::new(seed);
 
-        let mut chunks: Vec<Chunk> =
-            Vec::with_capacity(METACHUNKSIZE * METACHUNKSIZE * METACHUNKSIZE);
-        for z in 0..METACHUNKSIZE {
-            for y in 0..METACHUNKSIZE {
-                for x in 0..METACHUNKSIZE {
+        let config = get_world_config();
+        let mut chunks: Vec<Chunk> = Vec::with_capacity(
+            get_meta_chunk_size() * get_meta_chunk_size() * config.get_height_in_chunks(),
+        );
+        for z in 0..get_meta_chunk_size() {
+            for y in config.min_chunk_y..config.max_chunk_y {
+                for x in 0..get_meta_chunk_size() {
                     let local_pos = &ChunkPos {
-                        x: x as i32 + pos.x * METACHUNKSIZE as i32,
-                        y: y as i32,
-                        z: z as i32 + pos.z * METACHUNKSIZE as i32,
+                        x: x as i32 + pos.x * get_meta_chunk_size() as i32,
+                        y,
+                        z: z as i32 + pos.z * get_meta_chunk_size() as i32,
                     };
                     chunks.push(chunk_generator.full_generation_pass(local_pos));
                 }
             }
         }
 
-        let mut chunk = MetaChunk { pos, chunks, seed };
+        let mut chunk = MetaChunk {
+            pos,
+            chunks,
+            seed,
+            outgoing_writes: Vec::new(),
+            modified: false,
+        };
 
-        let structure_x = pos.x * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + 20;
-        let structure_z = pos.z * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + 20;
+        let structure_x = pos.x * get_world_config().get_meta_chunk_blocks() as i32 + 20;
+        let structure_z = pos.z * get_world_config().get_meta_chunk_blocks() as i32 + 20;
         let structure_y = chunk.first_above_land_y(structure_x, structure_z);
         let global_center_pos = GlobalBlockPos {
             x: structure_x,
             y: structure_y,
             z: structure_z,
         };
-        bfs_world_air(
-            &global_center_pos,
-            5,
-            &mut chunk,
-            get_blockid(BlockType::Sand),
-        );
+        bfs_world_air(&global_center_pos, 5, &mut chunk, SAND);
 
-        let structure_x = pos.x * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + 3;
-        let structure_z = pos.z * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + 60;
+        let structure_x = pos.x * get_world_config().get_meta_chunk_blocks() as i32 + 3;
+        let structure_z = pos.z * get_world_config().get_meta_chunk_blocks() as i32 + 60;
         let structure_y = chunk.first_above_land_y(structure_x, structure_z);
         let global_center_pos = GlobalBlockPos {
             x: structure_x,
@@ -77,8 +112,8 @@ impl MetaChunk {
         };
         place_square(&global_center_pos, 10, &mut chunk);
 
-        let mut rng = rand::thread_rng();
-        let location_range = Uniform::from(5..(METACHUNKSIZE * CHUNKSIZE) - 5);
+        let mut rng = PositionRng::from_meta_chunk(seed, &pos, TREE_PLACEMENT_SALT);
+        let location_range = Uniform::from(5..get_world_config().get_meta_chunk_blocks() - 5);
         let normal_distribution = Normal::new(0f32, 50f32).unwrap();
         let x_offset = location_range.sample(&mut rng) as i32;
         let z_offset = location_range.sample(&mut rng) as i32;
@@ -86,26 +121,23 @@ impl MetaChunk {
             let x_diff: i32 = normal_distribution.sample(&mut rng) as i32;
             let z_diff: i32 = normal_distribution.sample(&mut rng) as i32;
 
-            let structure_x = pos.x * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + x_offset + x_diff;
-            let structure_z = pos.z * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + z_offset + z_diff;
+            let structure_x =
+                pos.x * get_world_config().get_meta_chunk_blocks() as i32 + x_offset + x_diff;
+            let structure_z =
+                pos.z * get_world_config().get_meta_chunk_blocks() as i32 + z_offset + z_diff;
             let structure_y = chunk.first_above_land_y(structure_x, structure_z);
             let tree_pos = GlobalBlockPos {
                 x: structure_x,
                 y: structure_y,
                 z: structure_z,
             };
-            match chunk.get_block(&tree_pos.get_diff(0, -1, 0)) {
-                None => {}
-                Some(b) => {
-                    if get_blocktype(b) == BlockType::Grass {
-                        place_tree(&tree_pos, &mut chunk);
-                    }
-                }
+            if chunk.get_block(&tree_pos.get_diff(0, -1, 0)) == Some(GRASS) {
+                place_tree(&tree_pos, seed, &mut chunk);
             }
         }
 
-        let structure_x = pos.x * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + pos.x;
-        let structure_z = pos.z * METACHUNKSIZE as i32 * CHUNKSIZE as i32 + pos.z;
+        let structure_x = pos.x * get_world_config().get_meta_chunk_blocks() as i32 + pos.x;
+        let structure_z = pos.z * get_world_config().get_meta_chunk_blocks() as i32 + pos.z;
         for y in chunk.first_above_land_y(structure_x, structure_z)
             ..chunk.first_above_land_y(structure_x, structure_z) + 10
         {
@@ -114,20 +146,16 @@ impl MetaChunk {
                 y,
                 z: structure_z,
             };
-            chunk.set_block(&global_center_pos, get_blockid(BlockType::Sand));
+            chunk.set_block(&global_center_pos, SAND);
         }
+        chunk.apply_deferred_writes(&load_deferred_writes(&pos));
 
         return chunk;
     }
     pub fn first_above_land_y(&self, x: i32, z: i32) -> i32 {
-        let mut y = METACHUNKSIZE as i32 * CHUNKSIZE as i32 - 1;
+        let mut y = get_world_config().get_max_y() - 1;
         while let Some(b) = self.get_block(&GlobalBlockPos { x, y, z }) {
-            let b_type = get_blocktype(b);
-            if b_type == BlockType::Grass
-                || b_type == BlockType::Water
-                || b_type == BlockType::Dirt
-                || b_type == BlockType::Stone
-            {
+            if get_block_registry().get(b).properties.ground {
                 return y + 1;
             }
             y -= 1;
@@ -135,98 +163,115 @@ impl MetaChunk {
         return y;
     }
 
-    pub fn load_from_disk(pos: &MetaChunkPos) -> Option<MetaChunk> {
-        let filename = format!("{}-{}.txt", pos.x, pos.z);
-        return read_meta_chunk_from_file(filename.as_str());
+    pub fn load_from_disk(pos: &MetaChunkPos) -> Result<Option<MetaChunk>, IoError> {
+        return read_from_region(&get_region_dir(), pos.x, pos.z);
     }
 
-    pub fn save_to_disk(&self) {
-        let filename = format!("{}-{}.txt", self.pos.x, self.pos.z);
-        write_to_file(filename.as_str(), self)
+    pub fn save_to_disk(&self) -> Result<(), IoError> {
+        return write_to_region(&get_region_dir(), self.pos.x, self.pos.z, self);
     }
-
-    pub fn set_block(&mut self, pos: &GlobalBlockPos, block: BlockId) {
-        let chunk_pos = pos.get_local_chunk();
-        let chunk = self.get_chunk_mut(&chunk_pos);
-        match chunk {
-            Some(c) => c.set_block(block, &pos.get_local_pos()),
-            None => {}
-        }
+    //called when A copy of the metachunk is handed over for saving, the chunk generation thread
+    //removes its deferred writes from disk once that copy is written
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
     }
 
-    pub fn get_block(&self, pos: &GlobalBlockPos) -> Option<BlockId> {
-        if !(pos.x >= self.pos.x * METACHUNKSIZE as i32 * CHUNKSIZE as i32
-            && pos.x < (self.pos.x + 1) * METACHUNKSIZE as i32 * CHUNKSIZE as i32
-            && pos.z >= self.pos.z * METACHUNKSIZE as i32 * CHUNKSIZE as i32
-            && pos.z < (self.pos.z + 1) * METACHUNKSIZE as i32 * CHUNKSIZE as i32)
-        {
-            return None;
-        }
-        let chunk_pos = pos.get_local_chunk();
-        let chunk = self.get_chunk(&chunk_pos);
-        match chunk {
-            Some(c) => c.get_block(&pos.get_local_pos()),
-            None => None,
+    //writes from structures of neighboring metachunks only replace blocks like air and plants,
+    //so they never cut into the terrain
+    pub fn apply_deferred_writes(&mut self, writes: &[DeferredWrite]) {
+        let registry = get_block_registry();
+        for write in writes {
+            match self.get_block(&write.pos) {
+                Some(b) if registry.get(b).properties.replaceable => {
+                    self.set_block_state(&write.pos, write.state)
+                }
+                _ => {}
+            }
         }
     }
+    pub fn take_outgoing_writes(&mut self) -> Vec<DeferredWrite> {
+        return std::mem::take(&mut self.outgoing_writes);
+    }
+    pub fn is_modified(&self) -> bool {
+        return self.modified;
+    }
+    pub fn set_modified(&mut self) {
+        self.modified = true;
+    }
+
     pub fn for_each_mut(&mut self, f: impl Fn(&mut Chunk, ChunkPos)) {
-        for x in 0..METACHUNKSIZE as i32 {
-            for y in 0..METACHUNKSIZE as i32 {
-                for z in 0..METACHUNKSIZE as i32 {
+        for x in 0..get_meta_chunk_size() as i32 {
+            for y in 0..get_world_config().get_height_in_chunks() as i32 {
+                for z in 0..get_meta_chunk_size() as i32 {
                     let pos = ChunkPos {
-                        x: self.pos.x * METACHUNKSIZE as i32 + x,
-                        y,
-                        z: self.pos.z * METACHUNKSIZE as i32 + z,
+                        x: self.pos.x * get_meta_chunk_size() as i32 + x,
+                        y: y + get_world_config().min_chunk_y,
+                        z: self.pos.z * get_meta_chunk_size() as i32 + z,
                     };
-                    f(self.get_chunk_mut(&LocalChunkPos { x, y, z }).unwrap(), pos);
+                    f(
+                        self.get_local_chunk_mut(&LocalChunkPos { x, y, z })
+                            .unwrap(),
+                        pos,
+                    );
                 }
             }
         }
     }
     pub fn for_each(&self, f: fn(&Chunk, ChunkPos)) {
-        for x in 0..METACHUNKSIZE as i32 {
-            for y in 0..METACHUNKSIZE as i32 {
-                for z in 0..METACHUNKSIZE as i32 {
+        for x in 0..get_meta_chunk_size() as i32 {
+            for y in 0..get_world_config().get_height_in_chunks() as i32 {
+                for z in 0..get_meta_chunk_size() as i32 {
                     let pos = ChunkPos {
-                        x: self.pos.x * METACHUNKSIZE as i32 + x,
-                        y,
-                        z: self.pos.z * METACHUNKSIZE as i32 + z,
+                        x: self.pos.x * get_meta_chunk_size() as i32 + x,
+                        y: y + get_world_config().min_chunk_y,
+                        z: self.pos.z * get_meta_chunk_size() as i32 + z,
                     };
-                    f(self.get_chunk(&LocalChunkPos { x, y, z }).unwrap(), pos);
+                    f(
+                        self.get_local_chunk(&LocalChunkPos { x, y, z }).unwrap(),
+                        pos,
+                    );
                 }
             }
         }
     }
-    pub fn get_chunk_mut(&mut self, pos: &LocalChunkPos) -> Option<&mut Chunk> {
+    pub fn get_local_chunk_mut(&mut self, pos: &LocalChunkPos) -> Option<&mut Chunk> {
         return Some(
             self.chunks[pos.x as usize
-                + pos.y as usize * METACHUNKSIZE as usize
-                + pos.z as usize * METACHUNKSIZE as usize * METACHUNKSIZE as usize]
-                .borrow_mut(),
+                + pos.y as usize * get_meta_chunk_size()
+                + pos.z as usize
+                    * get_meta_chunk_size()
+                    * get_world_config().get_height_in_chunks()]
+            .borrow_mut(),
         );
     }
-    pub fn get_chunk(&self, pos: &LocalChunkPos) -> Option<&Chunk> {
+    pub fn get_local_chunk(&self, pos: &LocalChunkPos) -> Option<&Chunk> {
         return Some(
             &self.chunks[pos.x as usize
-                + pos.y as usize * METACHUNKSIZE as usize
-                + pos.z as usize * METACHUNKSIZE as usize * METACHUNKSIZE as usize],
+                + pos.y as usize * get_meta_chunk_size()
+                + pos.z as usize
+                    * get_meta_chunk_size()
+                    * get_world_config().get_height_in_chunks()],
         );
     }
     #[inline]
     pub fn retain_meta_chunk(player: &Player, pos: MetaChunkPos) -> bool {
-        let current_chunk = player.position.get_meta_chunk();
-        pos.x > current_chunk.x - METACHUNK_GEN_RANGE as i32 - 2
-            && pos.x < current_chunk.x + METACHUNK_GEN_RANGE as i32 + 2
-            && pos.z > current_chunk.z - METACHUNK_GEN_RANGE as i32 - 2
-            && pos.z < current_chunk.z + METACHUNK_GEN_RANGE as i32 + 2
+        let current_chunk = player.position.get_meta_chunk_pos();
+        let range = get_meta_chunk_gen_range() as i32;
+        pos.x > current_chunk.x - range - 2
+            && pos.x < current_chunk.x + range + 2
+            && pos.z > current_chunk.z - range - 2
+            && pos.z < current_chunk.z + range + 2
     }
     pub fn get_chunk_pos(&self, pos: &LocalChunkPos) -> ChunkPos {
-        let x = self.pos.x * METACHUNKSIZE as i32
-            + wrap(to_sign_of(self.pos.x, pos.x), METACHUNKSIZE as i32);
-        let y = pos.y;
-        let z = self.pos.z * METACHUNKSIZE as i32
-            + wrap(to_sign_of(self.pos.z, pos.z), METACHUNKSIZE as i32);
-        ChunkPos { x, y, z }
+        return pos.get_chunk_pos(&self.pos);
+    }
+    pub fn get_memory_usage(&self) -> usize {
+        return std::mem::size_of::<MetaChunk>()
+            + self
+                .chunks
+                .iter()
+                .map(|c| c.get_memory_usage())
+                .sum::<usize>();
     }
     pub fn get_iter(&self) -> MetaChunkIterator {
         MetaChunkIterator {
@@ -238,6 +283,38 @@ impl MetaChunk {
     }
 }
 
+//the loose {x}-{z}.txt files metachunks were once stored in are not read, nothing ever saved them
+impl SaveFormat for MetaChunk {
+    const FORMAT_VERSION: u32 = 1;
+}
+
+//only the chunks of this metachunk are accessible, everything outside of it is unloaded
+impl VoxelWorld for MetaChunk {
+    fn get_chunk(&self, pos: &ChunkPos) -> Option<&Chunk> {
+        if !get_world_config().contains_chunk_y(pos.y) || pos.get_meta_chunk_pos() != self.pos {
+            return None;
+        }
+        return self.get_local_chunk(&pos.get_local_chunk_pos());
+    }
+    fn get_chunk_mut(&mut self, pos: &ChunkPos) -> Option<&mut Chunk> {
+        if !get_world_config().contains_chunk_y(pos.y) || pos.get_meta_chunk_pos() != self.pos {
+            return None;
+        }
+        return self.get_local_chunk_mut(&pos.get_local_chunk_pos());
+    }
+    fn set_block_state(&mut self, pos: &GlobalBlockPos, state: BlockState) {
+        match self.get_chunk_mut(&pos.get_chunk_pos()) {
+            Some(c) => c.set_block_state(state, &pos.get_local_pos()),
+            None => {
+                if pos.y >= self.get_min_y() && pos.y < self.get_max_y() {
+                    self.outgoing_writes
+                        .push(DeferredWrite { pos: *pos, state });
+                }
+            }
+        }
+    }
+}
+
 pub struct MetaChunkIterator<'a> {
     meta_chunk: &'a MetaChunk,
     x: u32,
@@ -248,34 +325,32 @@ pub struct MetaChunkIterator<'a> {
 impl<'a> Iterator for MetaChunkIterator<'a> {
     type Item = (&'a Chunk, ChunkPos);
 
+    //goes through x first, then y and then z
     fn next(&mut self) -> Option<(&'a Chunk, ChunkPos)> {
-        if self.x == (METACHUNKSIZE - 1) as u32
-            && self.y == (METACHUNKSIZE - 1) as u32
-            && self.z == (METACHUNKSIZE - 1) as u32
-        {
+        let size = get_meta_chunk_size() as u32;
+        if self.z == size {
             return None;
         }
         let pos = ChunkPos {
-            x: self.meta_chunk.pos.x * METACHUNKSIZE as i32 + self.x as i32,
-            y: self.y as i32,
-            z: self.meta_chunk.pos.z * METACHUNKSIZE as i32 + self.z as i32,
+            x: self.meta_chunk.pos.x * size as i32 + self.x as i32,
+            y: self.y as i32 + get_world_config().min_chunk_y,
+            z: self.meta_chunk.pos.z * size as i32 + self.z as i32,
         };
         let c = self
             .meta_chunk
-            .get_chunk(&LocalChunkPos {
+            .get_local_chunk(&LocalChunkPos {
                 x: self.x as i32,
                 y: self.y as i32,
                 z: self.z as i32,
             })
             .unwrap();
 
-        if self.x == (METACHUNKSIZE) as u32 {
+        self.x += 1;
+        if self.x == size {
             self.x = 0;
             self.y += 1;
-        } else {
-            self.x += 1;
         }
-        if self.y == (METACHUNKSIZE) as u32 {
+        if self.y == get_world_config().get_height_in_chunks() as u32 {
             self.y = 0;
             self.z += 1;
         }
@@ -283,3 +358,86 @@ impl<'a> Iterator for MetaChunkIterator<'a> {
         return Some((c, pos));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::block::{AIR, STONE};
+    use vox_io::io::format::decode;
+
+    //the metachunk stored in the fixtures, empty except for two blocks
+    fn get_fixture_pos() -> MetaChunkPos {
+        MetaChunkPos { x: 1, z: -2 }
+    }
+    fn get_fixture_blocks() -> [(GlobalBlockPos, BlockState); 2] {
+        let origin = get_fixture_pos().get_center_pos();
+        [
+            (
+                GlobalBlockPos {
+                    x: origin.x as i32,
+                    y: 5,
+                    z: origin.z as i32,
+                },
+                BlockState::new(STONE),
+            ),
+            (
+                GlobalBlockPos {
+                    x: origin.x as i32 + 3,
+                    y: 70,
+                    z: origin.z as i32 - 4,
+                },
+                BlockState::new(GRASS),
+            ),
+        ]
+    }
+
+    fn assert_fixture(chunk: &MetaChunk) {
+        assert_eq!(chunk.pos, get_fixture_pos());
+        assert_eq!(chunk.seed, 1234);
+        assert_eq!(
+            chunk.chunks.len(),
+            get_meta_chunk_size()
+                * get_meta_chunk_size()
+                * get_world_config().get_height_in_chunks()
+        );
+        for (pos, state) in get_fixture_blocks().iter() {
+            assert_eq!(chunk.get_block_state(pos), Some(*state));
+            assert_eq!(
+                chunk.get_block_state(&pos.get_diff(0, 1, 0)),
+                Some(BlockState::new(AIR))
+            );
+        }
+    }
+
+    #[test]
+    fn version_1_metachunks_decode() {
+        let bytes = include_bytes!("../../tests/fixtures/meta_chunk_v1.bin");
+        assert_fixture(&decode::<MetaChunk>(bytes).unwrap());
+    }
+
+    #[test]
+    fn the_iterator_visits_every_chunk_of_the_metachunk_once() {
+        let pos = MetaChunkPos { x: -3, z: 2 };
+        let count = get_meta_chunk_size()
+            * get_meta_chunk_size()
+            * get_world_config().get_height_in_chunks();
+        let meta_chunk = MetaChunk {
+            chunks: vec![Chunk::new(BlockState::new(AIR)); count],
+            pos,
+            seed: 0,
+            outgoing_writes: Vec::new(),
+            modified: false,
+        };
+        let mut seen = std::collections::HashSet::new();
+        for (chunk, chunk_pos) in meta_chunk.get_iter() {
+            assert_eq!(chunk_pos.get_meta_chunk_pos(), pos);
+            assert!(get_world_config().contains_chunk_y(chunk_pos.y));
+            assert!(std::ptr::eq(
+                chunk,
+                meta_chunk.get_chunk(&chunk_pos).unwrap()
+            ));
+            assert!(seen.insert(chunk_pos));
+        }
+        assert_eq!(seen.len(), count);
+    }
+}
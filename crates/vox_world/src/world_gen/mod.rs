@@ -1,5 +1,8 @@
 pub mod basic;
 pub mod chunk;
 pub mod chunk_gen_thread;
+pub mod deferred_writes;
 pub mod meta_chunk;
+pub mod packed_array;
+pub mod position_rng;
 pub mod vertex_generation;
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+const WORD_BITS: usize = 64;
+
+//fixed length array of unsigned values which only uses as many bits per value as needed,
+//with 0 bits every value is 0 and nothing is allocated
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PackedArray {
+    bits: u8,
+    len: usize,
+    data: Vec<u64>,
+}
+
+impl PackedArray {
+    pub fn new(len: usize) -> PackedArray {
+        PackedArray {
+            bits: 0,
+            len,
+            data: Vec::new(),
+        }
+    }
+    //smallest amount of bits per value which can store values up to and including max, values
+    //never cross the boundary of A word because the amount of bits is always A power of two
+    pub fn get_bits_needed(max: usize) -> u8 {
+        let mut bits = 0u8;
+        while max >= 1 << bits {
+            bits = if bits == 0 { 1 } else { bits * 2 };
+        }
+        bits
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn get_bits(&self) -> u8 {
+        self.bits
+    }
+    pub fn get_max_value(&self) -> usize {
+        (1usize << self.bits) - 1
+    }
+    #[inline]
+    pub fn get(&self, index: usize) -> usize {
+        if self.bits == 0 {
+            return 0;
+        }
+        let bits = self.bits as usize;
+        let per_word = WORD_BITS / bits;
+        let word = self.data[index / per_word];
+        let shift = (index % per_word) * bits;
+        ((word >> shift) & self.get_mask()) as usize
+    }
+    //the value has to fit in the current amount of bits, see grow
+    #[inline]
+    pub fn set(&mut self, index: usize, value: usize) {
+        if self.bits == 0 {
+            return;
+        }
+        let bits = self.bits as usize;
+        let per_word = WORD_BITS / bits;
+        let shift = (index % per_word) * bits;
+        let mask = self.get_mask();
+        let word = &mut self.data[index / per_word];
+        *word = (*word & !(mask << shift)) | ((value as u64 & mask) << shift);
+    }
+    //repacks all values so values up to and including max fit
+    pub fn grow(&mut self, max: usize) {
+        let bits = PackedArray::get_bits_needed(max);
+        if bits <= self.bits {
+            return;
+        }
+        self.repack(bits);
+    }
+    //replaces every value by remap[value] and shrinks to the bits needed for the new values
+    pub fn remap(&mut self, remap: &[usize], max: usize) {
+        let mut packed = PackedArray::new(self.len);
+        packed.repack(PackedArray::get_bits_needed(max));
+        for i in 0..self.len {
+            packed.set(i, remap[self.get(i)]);
+        }
+        *self = packed;
+    }
+    //sets every value to 0 and frees the data
+    pub fn clear(&mut self) {
+        self.bits = 0;
+        self.data = Vec::new();
+    }
+    pub fn get_memory_usage(&self) -> usize {
+        std::mem::size_of::<PackedArray>() + self.data.len() * std::mem::size_of::<u64>()
+    }
+
+    fn repack(&mut self, bits: u8) {
+        if bits == 0 {
+            self.clear();
+            return;
+        }
+        let per_word = WORD_BITS / bits as usize;
+        let mut packed = PackedArray {
+            bits,
+            len: self.len,
+            data: vec![0; self.len.div_ceil(per_word)],
+        };
+        if self.bits != 0 {
+            for i in 0..self.len {
+                packed.set(i, self.get(i));
+            }
+        }
+        *self = packed;
+    }
+    #[inline]
+    fn get_mask(&self) -> u64 {
+        (1u64 << self.bits) - 1
+    }
+}
@@ -0,0 +1,55 @@
+use rand::{Error, RngCore};
+use vox_core::positions::{GlobalBlockPos, MetaChunkPos};
+
+//used to give different features at the same position their own random values
+pub const TREE_PLACEMENT_SALT: u64 = 1;
+pub const TREE_SHAPE_SALT: u64 = 2;
+
+//splitmix64, seeded by hashing the world seed together with A position. The same seed and
+//position always produce the same values, independent of generation order or thread
+pub struct PositionRng {
+    state: u64,
+}
+
+impl PositionRng {
+    pub fn new(seed: u32, x: i32, y: i32, z: i32, salt: u64) -> PositionRng {
+        let mut hash = mix(seed as u64 ^ mix(salt));
+        hash = mix(hash ^ x as u32 as u64);
+        hash = mix(hash ^ y as u32 as u64);
+        hash = mix(hash ^ z as u32 as u64);
+        PositionRng { state: hash }
+    }
+    pub fn from_meta_chunk(seed: u32, pos: &MetaChunkPos, salt: u64) -> PositionRng {
+        PositionRng::new(seed, pos.x, 0, pos.z, salt)
+    }
+    pub fn from_block(seed: u32, pos: &GlobalBlockPos, salt: u64) -> PositionRng {
+        PositionRng::new(seed, pos.x, pos.y, pos.z, salt)
+    }
+}
+
+impl RngCore for PositionRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix(self.state)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for bytes in dest.chunks_mut(8) {
+            let value = self.next_u64().to_le_bytes();
+            bytes.copy_from_slice(&value[..bytes.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
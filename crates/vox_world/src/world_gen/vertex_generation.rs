@@ -1,18 +1,22 @@
-use crate::blocks::block::{get_blocktype, get_mesh, should_render_against, BlockId};
-use crate::blocks::block_type::BlockType;
-use crate::blocks::blockside::BlockSides;
-use crate::world::small_world::SmallWorld;
+use crate::blocks::block::{get_mesh, should_render_against, AIR};
+use crate::blocks::block_registry::get_block_registry;
+use crate::blocks::block_state::BlockState;
+use crate::blocks::blockside::{BlockSides, Side};
+use crate::world::voxel_world::VoxelWorld;
 use crate::world_gen::chunk::Chunk;
 use std::time::Instant;
-use vox_core::constants::{CHUNKSIZE, METACHUNKSIZE};
 use vox_core::positions::{ChunkPos, GlobalBlockPos, LocalBlockPos};
+use vox_core::world_config::get_chunk_size;
 use vox_render::renderer::vertex::Vertex;
 
-pub fn get_chunk_vertices(world: &SmallWorld, chunk_pos: &ChunkPos) -> (Vec<Vertex>, Vec<u32>) {
+pub fn get_chunk_vertices(
+    world: &impl VoxelWorld,
+    chunk_pos: &ChunkPos,
+) -> (Vec<Vertex>, Vec<u32>) {
     return match world.get_chunk(chunk_pos) {
         None => (Vec::new(), Vec::new()),
         Some(chunk) => {
-            if chunk.is_completely_air {
+            if chunk.is_completely_air() || is_hidden_by_neighbors(world, chunk, chunk_pos) {
                 return (Vec::new(), Vec::new());
             }
             let mut transparant_vertices: Vec<Vertex> = Vec::with_capacity(10000);
@@ -21,17 +25,19 @@ pub fn get_chunk_vertices(world: &SmallWorld, chunk_pos: &ChunkPos) -> (Vec<Vert
             let mut opaque_vertices: Vec<Vertex> = Vec::with_capacity(20000);
             let mut opaque_indices: Vec<u32> = Vec::with_capacity(20000);
 
-            for x in 0..CHUNKSIZE as i32 {
-                for y in 0..CHUNKSIZE as i32 {
-                    for z in 0..CHUNKSIZE as i32 {
+            for x in 0..get_chunk_size() as i32 {
+                for y in 0..get_chunk_size() as i32 {
+                    for z in 0..get_chunk_size() as i32 {
                         //let mut timer = Instant::now();
                         let global_pos = GlobalBlockPos {
-                            x: x + (chunk_pos.x * CHUNKSIZE as i32),
-                            y: y + (chunk_pos.y * CHUNKSIZE as i32),
-                            z: z + (chunk_pos.z * CHUNKSIZE as i32),
+                            x: x + (chunk_pos.x * get_chunk_size() as i32),
+                            y: y + (chunk_pos.y * get_chunk_size() as i32),
+                            z: z + (chunk_pos.z * get_chunk_size() as i32),
                         };
-                        let block = chunk.get_block(&LocalBlockPos { x, y, z }).unwrap();
-                        if get_blocktype(block) == BlockType::Air {
+                        let local_pos = LocalBlockPos { x, y, z };
+                        let state = chunk.get_block_state(&local_pos).unwrap();
+                        let block = state.id;
+                        if block == AIR {
                             continue;
                         }
                         let mut sides = sides_to_render(world, &global_pos);
@@ -39,8 +45,8 @@ pub fn get_chunk_vertices(world: &SmallWorld, chunk_pos: &ChunkPos) -> (Vec<Vert
                             continue;
                         }
                         let (mut temp_vertices, mut temp_indices) =
-                            get_mesh(block, &global_pos, &sides);
-                        if get_blocktype(block) == BlockType::Water {
+                            get_mesh(state, &local_pos, &sides);
+                        if get_block_registry().get(block).transparent {
                             temp_indices = temp_indices
                                 .iter()
                                 .map(|i| i + (&transparant_vertices).len() as u32)
@@ -73,40 +79,77 @@ pub fn get_chunk_vertices(world: &SmallWorld, chunk_pos: &ChunkPos) -> (Vec<Vert
         }
     };
 }
-pub fn sides_to_render(world: &SmallWorld, global_pos: &GlobalBlockPos) -> BlockSides {
-    let mut sides = BlockSides::new();
-    let mut reference_block = world.get_block(*global_pos).unwrap();
-    if should_render_against_block(world, &global_pos.get_diff(1, 0, 0), reference_block) {
-        sides.right = true;
-    }
-    if should_render_against_block(world, &global_pos.get_diff(-1, 0, 0), reference_block) {
-        sides.left = true;
-    }
-    if should_render_against_block(world, &global_pos.get_diff(0, 1, 0), reference_block) {
-        sides.top = true;
-    }
-    if should_render_against_block(world, &global_pos.get_diff(0, -1, 0), reference_block) {
-        sides.bot = true;
-    }
-    if should_render_against_block(world, &global_pos.get_diff(0, 0, 1), reference_block) {
-        sides.back = true;
-    }
-    if should_render_against_block(world, &global_pos.get_diff(0, 0, -1), reference_block) {
-        sides.front = true;
+//A chunk full of opaque cubes surrounded by other such chunks has no visible faces
+fn is_hidden_by_neighbors(world: &impl VoxelWorld, chunk: &Chunk, chunk_pos: &ChunkPos) -> bool {
+    if !chunk.is_completely_opaque() {
+        return false;
     }
+    let neighbors = [
+        chunk_pos.get_diff(1, 0, 0),
+        chunk_pos.get_diff(-1, 0, 0),
+        chunk_pos.get_diff(0, 1, 0),
+        chunk_pos.get_diff(0, -1, 0),
+        chunk_pos.get_diff(0, 0, 1),
+        chunk_pos.get_diff(0, 0, -1),
+    ];
+    return neighbors.iter().all(|pos| match world.get_chunk(pos) {
+        None => false,
+        Some(neighbor) => neighbor.is_completely_opaque(),
+    });
+}
+pub fn sides_to_render(world: &impl VoxelWorld, global_pos: &GlobalBlockPos) -> BlockSides {
+    let mut sides = BlockSides::new();
+    let reference_block = world.get_block_state(global_pos).unwrap();
+    sides.right = should_render_against_block(
+        world,
+        &global_pos.get_diff(1, 0, 0),
+        reference_block,
+        Side::Right,
+    );
+    sides.left = should_render_against_block(
+        world,
+        &global_pos.get_diff(-1, 0, 0),
+        reference_block,
+        Side::Left,
+    );
+    sides.top = should_render_against_block(
+        world,
+        &global_pos.get_diff(0, 1, 0),
+        reference_block,
+        Side::Top,
+    );
+    sides.bot = should_render_against_block(
+        world,
+        &global_pos.get_diff(0, -1, 0),
+        reference_block,
+        Side::Bot,
+    );
+    sides.back = should_render_against_block(
+        world,
+        &global_pos.get_diff(0, 0, 1),
+        reference_block,
+        Side::Back,
+    );
+    sides.front = should_render_against_block(
+        world,
+        &global_pos.get_diff(0, 0, -1),
+        reference_block,
+        Side::Front,
+    );
     return sides;
 }
 #[inline]
 pub fn should_render_against_block(
-    world: &SmallWorld,
+    world: &impl VoxelWorld,
     pos: &GlobalBlockPos,
-    reference_block: BlockId,
+    reference_block: BlockState,
+    side: Side,
 ) -> bool {
-    if pos.y == (METACHUNKSIZE * CHUNKSIZE) as i32 || pos.y < 0 {
+    if pos.y >= world.get_max_y() || pos.y < world.get_min_y() {
         return true;
     }
-    return match world.get_block(*pos) {
+    return match world.get_block_state(pos) {
         None => true,
-        Some(b) => should_render_against(reference_block, b),
+        Some(b) => should_render_against(reference_block, b, side),
     };
 }